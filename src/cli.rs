@@ -1,6 +1,15 @@
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use anyhow::{Result, anyhow};
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+
+use crate::export::ExportFormat;
+use crate::filter::{AssigneeFilter, Filter, SortKey};
+use crate::import::ImportFormat;
+use crate::ops;
+use crate::storage::Store;
+use crate::util::parse_since;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -23,101 +32,754 @@ pub enum SubCommand {
     /// Create a new todo
     Add {
         /// Words that become the title
-        #[arg(required = true)]
+        #[arg(required_unless_present = "from_stdin", conflicts_with = "from_stdin")]
         text: Vec<String>,
-        /// Set body text (supports \n for newlines)
-        #[arg(long, value_name = "TEXT")]
+        /// Set body text, or `-` to read it from stdin
+        #[arg(long, value_name = "TEXT", conflicts_with = "from_stdin")]
         body: Option<String>,
+        /// Read the body from a file (`-` for stdin)
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["body", "from_stdin"])]
+        body_file: Option<PathBuf>,
+        /// Create one todo per line of stdin, or per Markdown document with
+        /// frontmatter, and print their IDs
+        #[arg(long)]
+        from_stdin: bool,
+        /// Expected effort, e.g. 2h, 1h30m, 3d
+        #[arg(long, value_name = "DURATION")]
+        estimate: Option<String>,
+        /// Due date, e.g. 2026-03-01, tomorrow, 3d
+        #[arg(long, value_name = "DATE")]
+        due: Option<String>,
+        /// Recur on completion: daily, weekly, monthly, yearly, "every 2 weeks",
+        /// or an RRULE subset such as FREQ=WEEKLY;BYDAY=MO,TH
+        #[arg(long, value_name = "RULE")]
+        recur: Option<String>,
+        /// Tag the todo (repeatable)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+        /// Start the body from .todo/templates/NAME.md
+        #[arg(long, value_name = "NAME", conflicts_with_all = ["body", "body_file", "from_stdin"])]
+        template: Option<String>,
     },
     /// Open a todo in $EDITOR, or update body with --body
     Edit {
-        /// Todo ID (or unique prefix)
-        id: String,
-        /// Set new body non-interactively
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        /// Set new body non-interactively, or `-` to read it from stdin
         #[arg(long, value_name = "TEXT")]
         body: Option<String>,
+        /// Read the new body from a file (`-` for stdin)
+        #[arg(long, value_name = "PATH", conflicts_with = "body")]
+        body_file: Option<PathBuf>,
     },
     /// Mark a todo as done
     Done {
-        /// Todo ID (or unique prefix)
-        id: String,
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
     },
     /// Reopen a done todo
     Reopen {
-        /// Todo ID (or unique prefix)
-        id: String,
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
     },
     /// Delete a todo
     Delete {
-        /// Todo ID (or unique prefix)
-        id: String,
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
         /// Delete without confirmation (non-interactive)
         #[arg(long)]
         force: bool,
     },
     /// List todos
     List {
-        /// Include done todos
-        #[arg(long)]
-        all: bool,
+        #[command(flatten)]
+        filter: FilterArgs,
+        /// Line layout, e.g. '{id} {status:>6} {title:.50} {created:ago}'
+        #[arg(long, value_name = "TEMPLATE")]
+        template: Option<String>,
     },
-    /// Assign a todo
+    /// Assign a todo to people (default: the current user)
     Assign {
-        /// Todo ID (or unique prefix)
-        id: String,
-        /// Optional assignee name
-        name: Option<String>,
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        /// Assignee names; defaults to $TDO_USER, `user` in config.yml or git user.name
+        names: Vec<String>,
     },
-    /// Unassign a todo
+    /// Remove assignees from a todo (default: all of them)
     Unassign {
-        /// Todo ID (or unique prefix)
-        id: String,
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        /// Assignees to remove
+        names: Vec<String>,
     },
     /// View a todo's full content
     #[command(alias = "view", alias = "get")]
     Show {
-        /// Todo ID (or unique prefix)
-        id: String,
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        /// Also print the todo's activity history
+        #[arg(long)]
+        history: bool,
+    },
+    /// Start a work timer on a todo (stops any other running timer)
+    Start {
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+    },
+    /// Stop the running work timer
+    Stop {
+        /// Todo ID, unique prefix, or title (`@title` to force one); defaults to whichever timer is running
+        id: Option<String>,
+    },
+    /// Record time spent on a todo, e.g. `tdo log-time a3f9 45m`
+    #[command(allow_missing_positional = true)]
+    LogTime {
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        /// Duration, e.g. 45m, 1h30m
+        duration: String,
+    },
+    /// Set or clear a todo's estimate
+    #[command(allow_missing_positional = true)]
+    Estimate {
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        /// Expected effort, e.g. 2h, 1h30m, 3d
+        #[arg(required_unless_present = "clear")]
+        duration: Option<String>,
+        /// Remove the estimate
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Add tags to a todo
+    #[command(allow_missing_positional = true)]
+    Tag {
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        #[arg(required = true)]
+        tags: Vec<String>,
     },
-    /// Print the number of todos
+    /// Remove tags from a todo
+    #[command(allow_missing_positional = true)]
+    Untag {
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Assign yourself the oldest open unassigned todo and print it. Exits
+    /// with status 3 if there is nothing to claim
+    Claim {
+        /// Only claim todos with this tag (repeatable; all must match)
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+        /// Claim as NAME instead of the current user
+        #[arg(long = "as", value_name = "NAME")]
+        name: Option<String>,
+    },
+    /// Give back a claimed todo
+    Release {
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        /// Release as NAME instead of the current user
+        #[arg(long = "as", value_name = "NAME")]
+        name: Option<String>,
+    },
+    /// Add a timestamped note to a todo without touching its body
+    #[command(alias = "comment")]
+    #[command(allow_missing_positional = true)]
+    Note {
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        /// Words of the note, or `-` to read it from stdin
+        #[arg(required = true)]
+        text: Vec<String>,
+        /// Attribute the note to NAME instead of the current user
+        #[arg(long = "as", value_name = "NAME")]
+        name: Option<String>,
+    },
+    /// List assignments older than `stale_after`, after applying the
+    /// configured `stale_action`
+    Stale,
+    /// Close a todo without marking it done (won't fix, no longer needed)
+    Cancel {
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        /// Why it was cancelled, recorded in the history
+        #[arg(long, value_name = "TEXT")]
+        reason: Option<String>,
+    },
+    /// Move a todo to a workflow state: open, done, or one of `statuses` in
+    /// .todo/config.yml
+    #[command(allow_missing_positional = true)]
+    Status {
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        /// e.g. in-progress, review, done
+        state: String,
+    },
+    /// Hide an open todo from lists until a date, e.g. `tdo snooze a3f9 monday`
+    #[command(allow_missing_positional = true)]
+    Snooze {
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        /// 3d, monday, 2026-12-01, ...
+        #[arg(required_unless_present = "clear")]
+        until: Option<String>,
+        /// Unsnooze the todo
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Set or clear a todo's due date
+    #[command(allow_missing_positional = true)]
+    Due {
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        /// Due date, e.g. 2026-03-01, tomorrow, 3d
+        #[arg(required_unless_present = "clear")]
+        date: Option<String>,
+        /// Remove the due date
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Make a todo recur: completing it creates the next occurrence
+    #[command(allow_missing_positional = true)]
+    Recur {
+        /// Todo ID, unique prefix, or title (`@title` to force one); picked from a list if omitted
+        id: Option<String>,
+        /// daily, weekly, monthly, yearly, "every 2 weeks", or FREQ=WEEKLY;BYDAY=MO
+        #[arg(required_unless_present = "clear")]
+        rule: Option<String>,
+        /// Stop recurring
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Summarise the store: counts, weekly throughput, cycle time, load
+    Report {
+        #[command(subcommand)]
+        report: Option<ReportCommand>,
+        /// Print the summary as JSON
+        #[arg(long)]
+        json: bool,
+        /// Number of weeks of created/completed counts
+        #[arg(long, value_name = "N", default_value_t = 4)]
+        weeks: usize,
+    },
+    /// Show recent activity across all todos
+    Log {
+        /// Only show the N most recent events
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+    },
+    /// Print the number of todos (takes the same filters as `list`)
     Count {
-        /// Include done todos
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+    /// Give a todo a fresh ID (e.g. to resolve a duplicate after a merge)
+    Reid {
+        /// Filename of the todo, e.g. a3f9-fix-the-bug.md
+        filename: String,
+    },
+    /// Pull (with rebase) the git repository containing the store and reload it
+    Sync {
+        /// Push after pulling
+        #[arg(long)]
+        push: bool,
+    },
+    /// Git merge driver: merge two versions of a todo file field by field
+    MergeDriver {
+        /// Common ancestor version (%O)
+        base: PathBuf,
+        /// Current version, overwritten with the result (%A)
+        ours: PathBuf,
+        /// Other branch's version (%B)
+        theirs: PathBuf,
+    },
+    /// Check todo files for problems
+    #[command(alias = "fsck")]
+    Doctor {
+        /// Repair the problems that can be fixed safely
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Create todos from another tool's export
+    Import {
+        /// Format of the export
+        #[arg(long, value_enum)]
+        from: ImportFormat,
+        /// The exported file
+        file: PathBuf,
+        /// Also import done and cancelled items
         #[arg(long)]
         all: bool,
+        /// Print what would be imported without creating anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print a shell completion script, e.g. `source <(tdo completions bash)`
+    Completions {
+        #[arg(value_enum)]
+        shell: CompletionShell,
+    },
+    /// Write the todos in another format, e.g. to share the backlog
+    Export {
+        /// Output format
+        #[arg(long, value_enum)]
+        to: ExportFormat,
+        #[command(flatten)]
+        filter: FilterArgs,
+        /// Write to this file instead of stdout
+        #[arg(long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Which todos `list`, `count` and `export` act on; see `filter::Filter`.
+#[derive(Args, Debug, Default)]
+pub struct FilterArgs {
+    /// Include done and cancelled todos
+    #[arg(long)]
+    pub all: bool,
+    /// Include open todos that are snoozed
+    #[arg(long)]
+    pub include_snoozed: bool,
+    /// Only todos in STATE (repeatable); done or cancelled ones don't need --all
+    #[arg(long, value_name = "STATE")]
+    pub status: Vec<String>,
+    /// Only todos assigned to the current user
+    #[arg(long, conflicts_with_all = ["assigned", "assignee", "unassigned"])]
+    pub mine: bool,
+    /// Only todos assigned to anyone
+    #[arg(long, conflicts_with_all = ["assignee", "unassigned"])]
+    pub assigned: bool,
+    /// Only todos assigned to NAME
+    #[arg(long, value_name = "NAME", conflicts_with = "unassigned")]
+    pub assignee: Option<String>,
+    /// Only todos nobody is assigned to
+    #[arg(long)]
+    pub unassigned: bool,
+    /// Only todos created at or after WHEN, e.g. 2026-02-01 or 7d
+    #[arg(long, value_name = "WHEN")]
+    pub created_after: Option<String>,
+    /// Only todos created before WHEN
+    #[arg(long, value_name = "WHEN")]
+    pub created_before: Option<String>,
+    /// Only todos done since WHEN; done ones don't need --all
+    #[arg(long, value_name = "WHEN")]
+    pub done_since: Option<String>,
+    /// Only todos whose title or body contains PATTERN (ignoring case)
+    #[arg(long, value_name = "PATTERN")]
+    pub grep: Option<String>,
+    /// Order of the todos
+    #[arg(long, value_enum, default_value_t)]
+    pub sort: SortKey,
+    /// Reverse the order
+    #[arg(long)]
+    pub reverse: bool,
+    /// Only the first N todos
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
+}
+
+impl FilterArgs {
+    /// Parse the dates and states, and resolve `--mine` to the current user.
+    pub fn into_filter(self, store: &Store) -> Result<Filter> {
+        let status = self
+            .status
+            .iter()
+            .map(|name| {
+                store.config.status(name).ok_or_else(|| {
+                    anyhow!(
+                        "unknown status '{name}' (expected {})",
+                        store.config.status_names().join(", ")
+                    )
+                })
+            })
+            .collect::<Result<_>>()?;
+        let assignee = if self.mine {
+            AssigneeFilter::Assignee(ops::current_user(store)?)
+        } else if let Some(name) = self.assignee {
+            AssigneeFilter::Assignee(name)
+        } else if self.assigned {
+            AssigneeFilter::Assigned
+        } else if self.unassigned {
+            AssigneeFilter::Unassigned
+        } else {
+            AssigneeFilter::Any
+        };
+        Ok(Filter {
+            all: self.all,
+            include_snoozed: self.include_snoozed,
+            status,
+            assignee,
+            created_after: self.created_after.as_deref().map(parse_since).transpose()?,
+            created_before: self
+                .created_before
+                .as_deref()
+                .map(parse_since)
+                .transpose()?,
+            done_since: self.done_since.as_deref().map(parse_since).transpose()?,
+            grep: self.grep,
+            sort: self.sort,
+            reverse: self.reverse,
+            limit: self.limit,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ReportCommand {
+    /// Time tracked per todo
+    Time {
+        /// Only count time after this date (2026-02-01) or duration ago (7d)
+        #[arg(long, value_name = "WHEN")]
+        since: Option<String>,
     },
 }
 
 pub enum Command {
-    Create { title: String, body: Option<String> },
-    Edit { id: String, body: Option<String> },
-    Done(String),
-    Reopen(String),
-    Delete { id: String, force: bool },
-    List { all: bool },
-    Assign { id: String, name: Option<String> },
-    Unassign(String),
-    View(String),
-    Count { all: bool },
+    Create {
+        title: String,
+        body: Option<String>,
+        body_file: Option<PathBuf>,
+        estimate: Option<String>,
+        due: Option<String>,
+        recur: Option<String>,
+        tags: Vec<String>,
+        template: Option<String>,
+    },
+    CreateFromStdin {
+        estimate: Option<String>,
+        due: Option<String>,
+        recur: Option<String>,
+        tags: Vec<String>,
+    },
+    Edit {
+        id: Option<String>,
+        body: Option<String>,
+        body_file: Option<PathBuf>,
+    },
+    Done(Option<String>),
+    Reopen(Option<String>),
+    Delete {
+        id: Option<String>,
+        force: bool,
+    },
+    List {
+        filter: FilterArgs,
+        template: Option<String>,
+    },
+    Assign {
+        id: Option<String>,
+        names: Vec<String>,
+    },
+    Unassign {
+        id: Option<String>,
+        names: Vec<String>,
+    },
+    View {
+        id: Option<String>,
+        history: bool,
+    },
+    Log {
+        limit: Option<usize>,
+    },
+    Start(Option<String>),
+    Stop(Option<String>),
+    LogTime {
+        id: Option<String>,
+        duration: String,
+    },
+    Estimate {
+        id: Option<String>,
+        duration: Option<String>,
+    },
+    Tag {
+        id: Option<String>,
+        tags: Vec<String>,
+    },
+    Untag {
+        id: Option<String>,
+        tags: Vec<String>,
+    },
+    Claim {
+        tags: Vec<String>,
+        name: Option<String>,
+    },
+    Release {
+        id: Option<String>,
+        name: Option<String>,
+    },
+    Note {
+        id: Option<String>,
+        text: String,
+        name: Option<String>,
+    },
+    Stale,
+    Cancel {
+        id: Option<String>,
+        reason: Option<String>,
+    },
+    Status {
+        id: Option<String>,
+        state: String,
+    },
+    Snooze {
+        id: Option<String>,
+        until: Option<String>,
+    },
+    Due {
+        id: Option<String>,
+        date: Option<String>,
+    },
+    Recur {
+        id: Option<String>,
+        rule: Option<String>,
+    },
+    Report {
+        json: bool,
+        weeks: usize,
+    },
+    ReportTime {
+        since: Option<String>,
+    },
+    Count(FilterArgs),
+    Reid(String),
+    Sync {
+        push: bool,
+    },
+    MergeDriver {
+        base: PathBuf,
+        ours: PathBuf,
+        theirs: PathBuf,
+    },
+    Doctor {
+        fix: bool,
+    },
+    Import {
+        from: ImportFormat,
+        file: PathBuf,
+        all: bool,
+        dry_run: bool,
+    },
+    Export {
+        to: ExportFormat,
+        filter: FilterArgs,
+        output: Option<PathBuf>,
+    },
+    Completions(CompletionShell),
     Tui,
     PlainList,
 }
 
+impl Command {
+    /// The todo the command acts on, as given on the command line; `None`
+    /// when it was left out, for the user to pick one.
+    pub fn id_mut(&mut self) -> Option<&mut Option<String>> {
+        match self {
+            Command::Edit { id, .. }
+            | Command::Delete { id, .. }
+            | Command::Assign { id, .. }
+            | Command::Unassign { id, .. }
+            | Command::View { id, .. }
+            | Command::LogTime { id, .. }
+            | Command::Estimate { id, .. }
+            | Command::Tag { id, .. }
+            | Command::Untag { id, .. }
+            | Command::Release { id, .. }
+            | Command::Note { id, .. }
+            | Command::Cancel { id, .. }
+            | Command::Status { id, .. }
+            | Command::Snooze { id, .. }
+            | Command::Due { id, .. }
+            | Command::Recur { id, .. }
+            | Command::Done(id)
+            | Command::Reopen(id)
+            | Command::Start(id) => Some(id),
+            // Without an ID, `stop` stops whichever timer is running.
+            Command::Stop(id) if id.is_some() => Some(id),
+            _ => None,
+        }
+    }
+
+    /// What picking a todo for the command is for, e.g. "mark done".
+    pub fn action(&self) -> &'static str {
+        match self {
+            Command::Edit { .. } => "edit",
+            Command::Done(_) => "mark done",
+            Command::Reopen(_) => "reopen",
+            Command::Delete { .. } => "delete",
+            Command::View { .. } => "show",
+            Command::Start(_) => "start a timer on",
+            Command::Release { .. } => "release",
+            Command::Cancel { .. } => "cancel",
+            Command::Assign { .. } => "assign",
+            Command::Unassign { .. } => "unassign",
+            Command::LogTime { .. } => "log time on",
+            Command::Estimate { .. } => "estimate",
+            Command::Tag { .. } => "tag",
+            Command::Untag { .. } => "untag",
+            Command::Note { .. } => "add a note to",
+            Command::Status { .. } => "move",
+            Command::Snooze { .. } => "snooze",
+            Command::Due { .. } => "set the due date of",
+            Command::Recur { .. } => "set the recurrence of",
+            _ => "use",
+        }
+    }
+}
+
 pub fn resolve_command(cli: Cli, is_tty: bool) -> Command {
     match cli.command {
-        Some(SubCommand::Add { text, body }) => Command::Create {
+        Some(SubCommand::Add {
+            from_stdin: true,
+            estimate,
+            due,
+            recur,
+            tags,
+            ..
+        }) => Command::CreateFromStdin {
+            estimate,
+            due,
+            recur,
+            tags,
+        },
+        Some(SubCommand::Add {
+            text,
+            body,
+            body_file,
+            estimate,
+            due,
+            recur,
+            tags,
+            template,
+            ..
+        }) => Command::Create {
             title: text.join(" "),
             body,
+            body_file,
+            estimate,
+            due,
+            recur,
+            tags,
+            template,
+        },
+        Some(SubCommand::Edit {
+            id,
+            body,
+            body_file,
+        }) => Command::Edit {
+            id,
+            body,
+            body_file,
         },
-        Some(SubCommand::Edit { id, body }) => Command::Edit { id, body },
         Some(SubCommand::Done { id }) => Command::Done(id),
         Some(SubCommand::Reopen { id }) => Command::Reopen(id),
         Some(SubCommand::Delete { id, force }) => Command::Delete { id, force },
-        Some(SubCommand::List { all }) => Command::List { all },
-        Some(SubCommand::Assign { id, name }) => Command::Assign { id, name },
-        Some(SubCommand::Unassign { id }) => Command::Unassign(id),
-        Some(SubCommand::Count { all }) => Command::Count { all },
-        Some(SubCommand::Show { id }) => Command::View(id),
+        Some(SubCommand::List { filter, template }) => Command::List { filter, template },
+        Some(SubCommand::Assign { id, names }) => Command::Assign { id, names },
+        Some(SubCommand::Unassign { id, names }) => Command::Unassign { id, names },
+        Some(SubCommand::Count { filter }) => Command::Count(filter),
+        Some(SubCommand::Show { id, history }) => Command::View { id, history },
+        Some(SubCommand::Log { limit }) => Command::Log { limit },
+        Some(SubCommand::Start { id }) => Command::Start(id),
+        Some(SubCommand::Stop { id }) => Command::Stop(id),
+        Some(SubCommand::LogTime { id, duration }) => Command::LogTime { id, duration },
+        Some(SubCommand::Estimate {
+            id,
+            duration,
+            clear,
+        }) => {
+            let (id, duration) = id_and_value("estimate", id, duration, clear);
+            Command::Estimate { id, duration }
+        }
+        Some(SubCommand::Tag { id, tags }) => Command::Tag { id, tags },
+        Some(SubCommand::Untag { id, tags }) => Command::Untag { id, tags },
+        Some(SubCommand::Claim { tags, name }) => Command::Claim { tags, name },
+        Some(SubCommand::Release { id, name }) => Command::Release { id, name },
+        Some(SubCommand::Note { id, text, name }) => Command::Note {
+            id,
+            text: text.join(" "),
+            name,
+        },
+        Some(SubCommand::Stale) => Command::Stale,
+        Some(SubCommand::Cancel { id, reason }) => Command::Cancel { id, reason },
+        Some(SubCommand::Status { id, state }) => Command::Status { id, state },
+        Some(SubCommand::Snooze { id, until, clear }) => {
+            let (id, until) = id_and_value("snooze", id, until, clear);
+            Command::Snooze { id, until }
+        }
+        Some(SubCommand::Due { id, date, clear }) => {
+            let (id, date) = id_and_value("due", id, date, clear);
+            Command::Due { id, date }
+        }
+        Some(SubCommand::Recur { id, rule, clear }) => {
+            let (id, rule) = id_and_value("recur", id, rule, clear);
+            Command::Recur { id, rule }
+        }
+        Some(SubCommand::Report {
+            report: Some(ReportCommand::Time { since }),
+            ..
+        }) => Command::ReportTime { since },
+        Some(SubCommand::Report {
+            report: None,
+            json,
+            weeks,
+        }) => Command::Report { json, weeks },
+        Some(SubCommand::Reid { filename }) => Command::Reid(filename),
+        Some(SubCommand::Sync { push }) => Command::Sync { push },
+        Some(SubCommand::MergeDriver { base, ours, theirs }) => {
+            Command::MergeDriver { base, ours, theirs }
+        }
+        Some(SubCommand::Doctor { fix }) => Command::Doctor { fix },
+        Some(SubCommand::Import {
+            from,
+            file,
+            all,
+            dry_run,
+        }) => Command::Import {
+            from,
+            file,
+            all,
+            dry_run,
+        },
+        Some(SubCommand::Export { to, filter, output }) => Command::Export { to, filter, output },
+        Some(SubCommand::Completions { shell }) => Command::Completions(shell),
         None if is_tty => Command::Tui,
         None => Command::PlainList,
     }
 }
+
+/// The ID and value of an `[ID] <VALUE>` command that can `--clear` the
+/// value instead. A lone positional is the value, except with `--clear`,
+/// where it is the ID; passing a value as well as `--clear` is an error.
+fn id_and_value(
+    command: &str,
+    id: Option<String>,
+    value: Option<String>,
+    clear: bool,
+) -> (Option<String>, Option<String>) {
+    if !clear {
+        return (id, value);
+    }
+    if id.is_some() && value.is_some() {
+        let mut cli = Cli::command();
+        cli.build();
+        cli.find_subcommand_mut(command)
+            .expect("known subcommand")
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--clear takes no value; pass only the todo ID",
+            )
+            .exit();
+    }
+    (id.or(value), None)
+}
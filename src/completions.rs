@@ -0,0 +1,140 @@
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use clap::CommandFactory;
+use clap_complete::{Shell, generate};
+
+use crate::cli::{Cli, CompletionShell};
+use crate::storage::{self, Store};
+
+/// Write the completion script for `shell`: the script clap generates for
+/// the subcommands and flags, followed by a hook that asks `tdo` (with
+/// `COMPLETE=ids`) for todo IDs where one is expected.
+pub fn write_script(shell: CompletionShell, out: &mut dyn Write) -> io::Result<()> {
+    let (generator, hook) = match shell {
+        CompletionShell::Bash => (Shell::Bash, BASH_HOOK),
+        CompletionShell::Zsh => (Shell::Zsh, ZSH_HOOK),
+        CompletionShell::Fish => (Shell::Fish, FISH_HOOK),
+    };
+    generate(generator, &mut Cli::command(), "tdo", out);
+    out.write_all(hook.as_bytes())
+}
+
+/// Falls back to the generated `_tdo` unless `tdo` offers IDs.
+const BASH_HOOK: &str = r#"
+_tdo_ids() {
+    local ids=""
+    if [[ ${COMP_CWORD} -ge 2 && ${COMP_WORDS[COMP_CWORD]} != -* ]]; then
+        ids=$(COMPLETE=ids "$1" -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null | cut -f1)
+    fi
+    if [[ -n "${ids}" ]]; then
+        COMPREPLY=( ${ids} )
+    else
+        _tdo "$@"
+    fi
+}
+
+if [[ "${BASH_VERSINFO[0]}" -eq 4 && "${BASH_VERSINFO[1]}" -ge 4 || "${BASH_VERSINFO[0]}" -gt 4 ]]; then
+    complete -F _tdo_ids -o nosort -o bashdefault -o default tdo
+else
+    complete -F _tdo_ids -o bashdefault -o default tdo
+fi
+"#;
+
+/// Falls back to the generated `_tdo` unless `tdo` offers IDs, which are
+/// shown with their titles.
+const ZSH_HOOK: &str = r#"
+_tdo_ids() {
+    local -a ids
+    if (( CURRENT > 2 )) && [[ $PREFIX != -* ]]; then
+        ids=(${(f)"$(COMPLETE=ids $words[1] -- ${words[2,CURRENT]} 2>/dev/null)"})
+    fi
+    if (( $#ids )); then
+        ids=(${ids//$'\t'/:})
+        _describe -V -t todos 'todo' ids
+    else
+        _tdo "$@"
+    fi
+}
+
+compdef _tdo_ids tdo
+"#;
+
+/// Adds the IDs, with their titles, to the generated rules.
+const FISH_HOOK: &str = r#"
+function __fish_tdo_ids
+    set -l words (commandline -opc) (commandline -ct)
+    COMPLETE=ids $words[1] -- $words[2..-1] 2>/dev/null
+end
+
+complete -c tdo -n "not __fish_tdo_needs_command" -k -a "(__fish_tdo_ids)"
+"#;
+
+/// The todos to offer for the last of `words` (the command line after
+/// `tdo`, ending with the word being completed) as `id\ttitle` lines, open
+/// todos first. Empty unless that word is a subcommand's todo ID, i.e. its
+/// first positional `id`.
+pub fn id_candidates(words: &[String]) -> Vec<String> {
+    let Some((current, before)) = words.split_last() else {
+        return vec![];
+    };
+    if current.starts_with('-') {
+        return vec![];
+    }
+    let mut cli = Cli::command();
+    cli.build();
+    let mut dir: Option<PathBuf> = None;
+    let mut subcommand: Option<&clap::Command> = None;
+    let mut positionals = 0;
+    let mut only_positionals = false;
+    let mut rest = before.iter();
+    while let Some(word) = rest.next() {
+        let command = subcommand.unwrap_or(&cli);
+        if !only_positionals && word == "--" {
+            only_positionals = true;
+        } else if !only_positionals && let Some(long) = word.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (long, None),
+            };
+            let takes_value = command
+                .get_arguments()
+                .find(|a| a.get_long() == Some(name))
+                .is_some_and(|a| a.get_action().takes_values());
+            let value = match value {
+                None if takes_value => rest.next().cloned(),
+                value => value,
+            };
+            if name == "dir" {
+                dir = value.map(PathBuf::from);
+            }
+        } else if !only_positionals && word.starts_with('-') {
+            // Short flags (`-h`) take no value.
+        } else if subcommand.is_none() {
+            match cli.find_subcommand(word) {
+                Some(found) => subcommand = Some(found),
+                None => return vec![],
+            }
+        } else {
+            positionals += 1;
+        }
+    }
+    let Some(subcommand) = subcommand else {
+        return vec![];
+    };
+    let wants_id = subcommand
+        .get_positionals()
+        .next()
+        .is_some_and(|a| a.get_id() == "id");
+    if !wants_id || positionals > 0 {
+        return vec![];
+    }
+
+    let mut todos = storage::peek_todos(&Store::resolve_dir(dir.as_deref()));
+    todos.sort_by_key(|t| !t.is_open());
+    todos
+        .into_iter()
+        .filter(|t| t.id.starts_with(current.as_str()))
+        .map(|t| format!("{}\t{}", t.id, t.title()))
+        .collect()
+}
@@ -0,0 +1,277 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use jiff::SignedDuration;
+use serde::{Deserialize, Deserializer};
+
+use crate::line_format::LineFormat;
+use crate::todo::Status;
+use crate::util::parse_duration;
+
+/// Name of the per-store config file inside the todo directory.
+pub const CONFIG_FILE: &str = "config.yml";
+
+/// Statuses that always exist; `statuses` adds to them.
+const BUILTIN_STATUSES: [&str; 3] = ["open", "done", "cancelled"];
+
+/// Per-store settings, read from `.todo/config.yml`. Every field is optional;
+/// a missing file means all defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Commit every change to the store with git.
+    pub auto_commit: bool,
+    /// Who "me" is, for `tdo assign` without a name and `tdo list --mine`.
+    /// `$TDO_USER` takes precedence; `git config user.name` is the fallback.
+    pub user: Option<String>,
+    /// Open assignments older than this are stale, e.g. `7d` or `36h`.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub stale_after: SignedDuration,
+    /// What happens to stale assignments.
+    pub stale_action: StaleAction,
+    /// Workflow states beyond the built-in `open`, `done` and `cancelled`,
+    /// in cycling order.
+    pub statuses: Vec<StatusConfig>,
+    /// Template from `.todo/templates/` for new todos added without a body.
+    pub default_template: Option<String>,
+    /// Line template for `tdo list` without `--template`.
+    pub list_template: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            auto_commit: false,
+            user: None,
+            stale_after: SignedDuration::from_hours(7 * 24),
+            stale_action: StaleAction::default(),
+            statuses: vec![],
+            default_template: None,
+            list_template: None,
+        }
+    }
+}
+
+/// A configured workflow state, written either as a bare name (`review`) or
+/// as a map (`{ name: wontfix, open: false, color: red }`).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "StatusEntry")]
+pub struct StatusConfig {
+    pub name: String,
+    /// Whether todos in this state count as open: listed by default and
+    /// shown as pending. Defaults to true.
+    pub open: bool,
+    pub color: Option<StatusColor>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StatusEntry {
+    Name(String),
+    Table(StatusTable),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StatusTable {
+    name: String,
+    #[serde(default = "default_open")]
+    open: bool,
+    #[serde(default)]
+    color: Option<StatusColor>,
+}
+
+fn default_open() -> bool {
+    true
+}
+
+impl From<StatusEntry> for StatusConfig {
+    fn from(entry: StatusEntry) -> StatusConfig {
+        match entry {
+            StatusEntry::Name(name) => StatusConfig {
+                name,
+                open: true,
+                color: None,
+            },
+            StatusEntry::Table(t) => StatusConfig {
+                name: t.name,
+                open: t.open,
+                color: t.color,
+            },
+        }
+    }
+}
+
+/// Color for a status label in `tdo list` and the TUI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusColor {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+}
+
+/// Policy for assignments older than `stale_after`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StaleAction {
+    /// Only report them (`tdo list` on a terminal, `tdo stale`, `tdo report`).
+    #[default]
+    Warn,
+    /// Remove the assignees so the todo can be claimed again.
+    Unassign,
+    /// Keep the assignees and add the `stale` tag.
+    Tag,
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(d: D) -> Result<SignedDuration, D::Error> {
+    let s = String::deserialize(d)?;
+    match parse_duration(&s) {
+        Ok(duration) if duration.is_positive() => Ok(duration),
+        Ok(_) => Err(serde::de::Error::custom("stale_after must be positive")),
+        Err(e) => Err(serde::de::Error::custom(format!("{e:#}"))),
+    }
+}
+
+impl Config {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(CONFIG_FILE);
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to read: {}", path.display()));
+            }
+        };
+        if raw.trim().is_empty() {
+            return Ok(Config::default());
+        }
+        let config: Config = serde_yml::from_str(&raw)
+            .with_context(|| format!("invalid config: {}", path.display()))?;
+        config
+            .validate()
+            .with_context(|| format!("invalid config: {}", path.display()))?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (i, status) in self.statuses.iter().enumerate() {
+            let name = &status.name;
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                bail!("status '{name}' must be a single non-empty word");
+            }
+            if BUILTIN_STATUSES.contains(&name.as_str()) {
+                bail!("status '{name}' is built in");
+            }
+            if self.statuses[..i].iter().any(|s| s.name == *name) {
+                bail!("status '{name}' is listed twice");
+            }
+        }
+        if let Some(template) = &self.list_template {
+            LineFormat::parse(template)?;
+        }
+        Ok(())
+    }
+
+    /// Resolve a status name: a built-in one or one of `statuses`.
+    pub fn status(&self, name: &str) -> Option<Status> {
+        if BUILTIN_STATUSES.contains(&name) {
+            return Some(Status::from(name.to_string()));
+        }
+        self.statuses
+            .iter()
+            .find(|s| s.name == name)
+            .map(|s| Status::Custom {
+                name: s.name.clone(),
+                open: s.open,
+            })
+    }
+
+    /// Every valid status name, built-in ones first.
+    pub fn status_names(&self) -> Vec<String> {
+        BUILTIN_STATUSES
+            .into_iter()
+            .map(str::to_string)
+            .chain(self.statuses.iter().map(|s| s.name.clone()))
+            .collect()
+    }
+
+    /// The configured color for `status`, if any.
+    pub fn status_color(&self, status: &Status) -> Option<StatusColor> {
+        self.statuses
+            .iter()
+            .find(|s| s.name == status.name())
+            .and_then(|s| s.color)
+    }
+
+    /// The state after `status` when cycling through the open states:
+    /// `open`, then each configured open state in order, then `open` again.
+    pub fn next_open_status(&self, status: &Status) -> Status {
+        let open: Vec<Status> = std::iter::once(Status::Open)
+            .chain(
+                self.statuses
+                    .iter()
+                    .filter(|s| s.open)
+                    .filter_map(|s| self.status(&s.name)),
+            )
+            .collect();
+        let next = open
+            .iter()
+            .position(|s| s == status)
+            .map_or(0, |i| (i + 1) % open.len());
+        open[next].clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_config_is_default() {
+        let config: Config = serde_yml::from_str("{}").unwrap();
+        assert!(!config.auto_commit);
+        assert_eq!(config.stale_after, SignedDuration::from_hours(7 * 24));
+        assert_eq!(config.stale_action, StaleAction::Warn);
+    }
+
+    #[test]
+    fn stale_policy() {
+        let config: Config =
+            serde_yml::from_str("stale_after: 3d\nstale_action: unassign").unwrap();
+        assert_eq!(config.stale_after, SignedDuration::from_hours(72));
+        assert_eq!(config.stale_action, StaleAction::Unassign);
+        assert!(serde_yml::from_str::<Config>("stale_after: soon").is_err());
+        assert!(serde_yml::from_str::<Config>("stale_action: delete").is_err());
+    }
+
+    #[test]
+    fn statuses_cycle_through_open_states() {
+        let config: Config = serde_yml::from_str(
+            "statuses:\n- in-progress\n- {name: blocked, color: red}\n- {name: wontfix, open: false}\n",
+        )
+        .unwrap();
+        config.validate().unwrap();
+        let blocked = config.status("blocked").unwrap();
+        assert_eq!(config.status_color(&blocked), Some(StatusColor::Red));
+        assert_eq!(config.next_open_status(&Status::Open).name(), "in-progress");
+        assert_eq!(config.next_open_status(&blocked), Status::Open);
+        assert_eq!(config.next_open_status(&Status::Done), Status::Open);
+        assert!(config.status("review").is_none());
+
+        let twice: Config = serde_yml::from_str("statuses: [review, review]").unwrap();
+        assert!(twice.validate().is_err());
+        let builtin: Config = serde_yml::from_str("statuses: [done]").unwrap();
+        assert!(builtin.validate().is_err());
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(serde_yml::from_str::<Config>("auto_comit: true").is_err());
+    }
+}
@@ -0,0 +1,575 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+use crate::config::Config;
+use crate::storage::{Store, id_from_filename, reid_filename, todo_filename};
+use crate::todo::{self, Frontmatter};
+use crate::util::generate_id;
+
+/// Upper bound on repair passes, so a fix that doesn't converge can't loop forever.
+const MAX_FIX_PASSES: usize = 10;
+
+/// A single problem found in the todo directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub filename: String,
+    /// 1-based line number within the file, when the problem has one.
+    pub line: Option<usize>,
+    pub kind: ProblemKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProblemKind {
+    /// Windows line endings, which hide the `---` delimiters from the parser.
+    CrlfLineEndings,
+    MissingOpening,
+    MissingClosing,
+    InvalidYaml(String),
+    /// A status that isn't `open`, `done` or configured; `expected` lists the valid ones.
+    InvalidStatus {
+        value: String,
+        expected: Vec<String>,
+    },
+    /// Valid YAML that doesn't match the frontmatter schema (e.g. missing title).
+    InvalidFrontmatter(String),
+    DoneAtOnOpen,
+    DuplicateId {
+        id: String,
+        others: Vec<String>,
+    },
+    /// The ID in the filename contains uppercase hex digits.
+    UppercaseId,
+    /// A parseable todo whose filename doesn't start with an ID, so tdo never loads it.
+    MissingId,
+    /// A `.md` file that is neither named nor shaped like a todo.
+    NotATodo,
+}
+
+impl ProblemKind {
+    /// Whether `tdo doctor --fix` can repair this without guessing.
+    pub fn is_fixable(&self) -> bool {
+        match self {
+            ProblemKind::CrlfLineEndings
+            | ProblemKind::DoneAtOnOpen
+            | ProblemKind::UppercaseId
+            | ProblemKind::MissingId
+            | ProblemKind::DuplicateId { .. } => true,
+            ProblemKind::InvalidStatus { value, expected } => {
+                normalize_status(value, expected).is_some()
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ProblemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProblemKind::CrlfLineEndings => write!(f, "CRLF line endings"),
+            ProblemKind::MissingOpening => write!(f, "missing opening --- delimiter"),
+            ProblemKind::MissingClosing => write!(f, "missing closing --- delimiter"),
+            ProblemKind::InvalidYaml(msg) => write!(f, "invalid YAML: {msg}"),
+            ProblemKind::InvalidStatus { value, expected } => {
+                write!(
+                    f,
+                    "invalid status '{value}' (expected {})",
+                    expected.join(", ")
+                )
+            }
+            ProblemKind::InvalidFrontmatter(msg) => write!(f, "invalid frontmatter: {msg}"),
+            ProblemKind::DoneAtOnOpen => write!(f, "open todo has a done_at timestamp"),
+            ProblemKind::DuplicateId { id, others } => {
+                write!(
+                    f,
+                    "duplicate id '{id}' (also used by {})",
+                    others.join(", ")
+                )
+            }
+            ProblemKind::UppercaseId => write!(f, "id in filename should be lowercase"),
+            ProblemKind::MissingId => {
+                write!(
+                    f,
+                    "todo file name doesn't start with an id, so it is never loaded"
+                )
+            }
+            ProblemKind::NotATodo => write!(f, "not a todo file"),
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{line}: {}", self.filename, self.kind),
+            None => write!(f, "{}: {}", self.filename, self.kind),
+        }
+    }
+}
+
+/// Check the store directory, print every problem, and optionally repair
+/// the fixable ones. Fails if any problems remain afterwards.
+pub fn run_doctor(store: &Store, fix: bool) -> Result<()> {
+    let dir = store.dir();
+    let mut out = io::stdout().lock();
+    if fix {
+        let messages = fix_all(dir, &store.config)?;
+        for message in &messages {
+            writeln!(out, "fixed: {message}")?;
+        }
+        if !messages.is_empty() {
+            store.commit("tdo: doctor --fix");
+        }
+    }
+
+    let problems = check(dir, &store.config)?;
+    for problem in &problems {
+        writeln!(out, "{problem}")?;
+    }
+    if problems.is_empty() {
+        eprintln!("no problems found");
+        return Ok(());
+    }
+    let fixable = problems.iter().filter(|p| p.kind.is_fixable()).count();
+    if !fix && fixable > 0 {
+        eprintln!("run `tdo doctor --fix` to repair {fixable} of them");
+    }
+    bail!(
+        "{} problem{} found",
+        problems.len(),
+        if problems.len() == 1 { "" } else { "s" }
+    )
+}
+
+/// Scan every `.md` file in `dir` and return the problems found, ordered by
+/// filename. Statuses are checked against `config`.
+pub fn check(dir: &Path, config: &Config) -> Result<Vec<Problem>> {
+    let mut problems = Vec::new();
+    let mut by_id: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for filename in md_files(dir)? {
+        let path = dir.join(&filename);
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read: {}", path.display()))?;
+        let content = check_content(&raw, config);
+        let parses = content
+            .iter()
+            .all(|(_, kind)| *kind == ProblemKind::DoneAtOnOpen);
+
+        let Some(id) = id_from_filename(&filename) else {
+            let kind = if parses {
+                ProblemKind::MissingId
+            } else {
+                ProblemKind::NotATodo
+            };
+            problems.push(Problem {
+                filename,
+                line: None,
+                kind,
+            });
+            continue;
+        };
+
+        by_id
+            .entry(id.to_ascii_lowercase())
+            .or_default()
+            .push(filename.clone());
+        if id.chars().any(|c| c.is_ascii_uppercase()) {
+            problems.push(Problem {
+                filename: filename.clone(),
+                line: None,
+                kind: ProblemKind::UppercaseId,
+            });
+        }
+        for (line, kind) in content {
+            problems.push(Problem {
+                filename: filename.clone(),
+                line,
+                kind,
+            });
+        }
+    }
+
+    for (id, filenames) in &by_id {
+        if filenames.len() < 2 {
+            continue;
+        }
+        for filename in filenames {
+            let others = filenames
+                .iter()
+                .filter(|f| *f != filename)
+                .cloned()
+                .collect();
+            problems.push(Problem {
+                filename: filename.clone(),
+                line: None,
+                kind: ProblemKind::DuplicateId {
+                    id: id.clone(),
+                    others,
+                },
+            });
+        }
+    }
+
+    problems.sort_by(|a, b| a.filename.cmp(&b.filename).then(a.line.cmp(&b.line)));
+    Ok(problems)
+}
+
+/// Check the content of a single file. Line numbers are 1-based.
+fn check_content(raw: &str, config: &Config) -> Vec<(Option<usize>, ProblemKind)> {
+    let mut problems = Vec::new();
+    let normalized;
+    let raw = if raw.contains("\r\n") {
+        problems.push((Some(1), ProblemKind::CrlfLineEndings));
+        normalized = raw.replace("\r\n", "\n");
+        normalized.as_str()
+    } else {
+        raw
+    };
+
+    let Some(rest) = raw.strip_prefix("---\n") else {
+        problems.push((Some(1), ProblemKind::MissingOpening));
+        return problems;
+    };
+    let Some((yaml, _)) = rest.split_once("\n---\n") else {
+        problems.push((Some(1), ProblemKind::MissingClosing));
+        return problems;
+    };
+
+    // Parse as a generic value first so syntax errors and schema errors
+    // can be told apart.
+    let value: serde_yml::Value = match serde_yml::from_str(yaml) {
+        Ok(v) => v,
+        Err(e) => {
+            problems.push((
+                yaml_error_line(&e),
+                ProblemKind::InvalidYaml(strip_location(&e)),
+            ));
+            return problems;
+        }
+    };
+    let mut bad_status = false;
+    let expected = config.status_names();
+    if let Some(status) = value.get("status").and_then(|s| s.as_str())
+        && normalize_status(status, &expected) != Some(status)
+    {
+        bad_status = true;
+        problems.push((
+            key_line(raw, "status"),
+            ProblemKind::InvalidStatus {
+                value: status.to_string(),
+                expected,
+            },
+        ));
+    }
+
+    match serde_yml::from_str::<Frontmatter>(yaml) {
+        Ok(fm) => {
+            let open = config.status(fm.status.name()).is_some_and(|s| s.is_open());
+            if open && fm.done_at.is_some() {
+                problems.push((key_line(raw, "done_at"), ProblemKind::DoneAtOnOpen));
+            }
+        }
+        Err(_) if bad_status => {}
+        Err(e) => problems.push((
+            yaml_error_line(&e),
+            ProblemKind::InvalidFrontmatter(strip_location(&e)),
+        )),
+    }
+    problems
+}
+
+/// Apply fixes until nothing fixable is left. Returns a description of each fix.
+fn fix_all(dir: &Path, config: &Config) -> Result<Vec<String>> {
+    let mut messages = Vec::new();
+    for _ in 0..MAX_FIX_PASSES {
+        let problems = check(dir, config)?;
+        // One fix per file per pass: a fix may rename or rewrite the file,
+        // which invalidates the other problems reported for it.
+        let mut touched = HashSet::new();
+        let mut fixed_any = false;
+        for problem in problems.iter().filter(|p| p.kind.is_fixable()) {
+            if touched.contains(&problem.filename) {
+                continue;
+            }
+            if let Some(message) = fix_problem(dir, problem, config)? {
+                touched.insert(problem.filename.clone());
+                messages.push(message);
+                fixed_any = true;
+            }
+        }
+        if !fixed_any {
+            break;
+        }
+    }
+    Ok(messages)
+}
+
+/// Repair a single problem. Returns `None` if it turned out not to be safe.
+fn fix_problem(dir: &Path, problem: &Problem, config: &Config) -> Result<Option<String>> {
+    let filename = &problem.filename;
+    let path = dir.join(filename);
+    match &problem.kind {
+        ProblemKind::CrlfLineEndings => {
+            let raw = read(&path)?;
+            write(&path, &raw.replace("\r\n", "\n"))?;
+            Ok(Some(format!("{filename}: converted CRLF line endings")))
+        }
+        ProblemKind::InvalidStatus { value, expected } => {
+            let Some(status) = normalize_status(value, expected) else {
+                return Ok(None);
+            };
+            let raw = read(&path)?;
+            let Some(line) = problem.line else {
+                return Ok(None);
+            };
+            let fixed: Vec<String> = raw
+                .split('\n')
+                .enumerate()
+                .map(|(i, l)| {
+                    if i + 1 == line {
+                        format!("status: {status}")
+                    } else {
+                        l.to_string()
+                    }
+                })
+                .collect();
+            write(&path, &fixed.join("\n"))?;
+            Ok(Some(format!(
+                "{filename}: changed status '{value}' to '{status}'"
+            )))
+        }
+        ProblemKind::DoneAtOnOpen => {
+            let raw = read(&path)?;
+            let (mut fm, body) = todo::parse_file(&raw, config)?;
+            if !fm.status.is_open() {
+                return Ok(None);
+            }
+            fm.done_at = None;
+            write(&path, &todo::render_file(&fm, body.as_deref())?)?;
+            Ok(Some(format!("{filename}: removed done_at from open todo")))
+        }
+        ProblemKind::UppercaseId => {
+            let lower = filename.to_ascii_lowercase();
+            let lower_id = &lower[..4];
+            if existing_ids(dir)?
+                .iter()
+                .filter(|id| *id == lower_id)
+                .count()
+                > 1
+            {
+                return Ok(None);
+            }
+            rename(dir, filename, &lower)?;
+            Ok(Some(format!("{filename}: renamed to {lower}")))
+        }
+        ProblemKind::DuplicateId { others, .. } => {
+            // The first file (by name) keeps the ID; every later one is renumbered.
+            let keeper_exists = others
+                .iter()
+                .any(|other| other < filename && dir.join(other).exists());
+            if !keeper_exists {
+                return Ok(None);
+            }
+            let ids = existing_ids(dir)?;
+            let id = generate_id(|candidate| ids.iter().any(|i| i == candidate))?;
+            let new_name = reid_filename(filename, &id);
+            rename(dir, filename, &new_name)?;
+            Ok(Some(format!("{filename}: renamed to {new_name}")))
+        }
+        ProblemKind::MissingId => {
+            let raw = read(&path)?;
+            let (fm, _) = todo::parse_file(&raw, config)?;
+            let ids = existing_ids(dir)?;
+            let id = generate_id(|candidate| ids.iter().any(|id| id == candidate))?;
+            let new_name = todo_filename(&id, &fm.title);
+            rename(dir, filename, &new_name)?;
+            Ok(Some(format!("{filename}: renamed to {new_name}")))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Names of all `.md` files in `dir`, sorted.
+fn md_files(dir: &Path) -> Result<Vec<String>> {
+    let entries = fs::read_dir(dir)
+        .with_context(|| format!("failed to read directory: {}", dir.display()))?;
+    let mut names = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("md") {
+            names.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Lowercased IDs of every todo-named file in `dir`, including unparseable ones.
+fn existing_ids(dir: &Path) -> Result<Vec<String>> {
+    Ok(md_files(dir)?
+        .iter()
+        .filter_map(|f| id_from_filename(f))
+        .map(|id| id.to_ascii_lowercase())
+        .collect())
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("failed to read: {}", path.display()))
+}
+
+fn write(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content).with_context(|| format!("failed to write: {}", path.display()))
+}
+
+fn rename(dir: &Path, from: &str, to: &str) -> Result<()> {
+    let target = dir.join(to);
+    if target.exists() {
+        bail!("cannot rename {from}: {to} already exists");
+    }
+    fs::rename(dir.join(from), &target).with_context(|| format!("failed to rename {from} to {to}"))
+}
+
+/// The canonical spelling of a status value among `expected`, if it only
+/// differs by case.
+fn normalize_status<'a>(value: &str, expected: &'a [String]) -> Option<&'a str> {
+    expected
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(value))
+        .map(String::as_str)
+}
+
+/// Line number of the first frontmatter line setting `key`.
+fn key_line(raw: &str, key: &str) -> Option<usize> {
+    raw.lines()
+        .position(|l| {
+            l.strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with(':'))
+        })
+        .map(|i| i + 1)
+}
+
+/// Map a YAML error location to a file line (the frontmatter starts on line 2).
+fn yaml_error_line(e: &serde_yml::Error) -> Option<usize> {
+    e.location().map(|loc| loc.line() + 1)
+}
+
+/// Drop the YAML-relative "at line N column M" suffix; we report file lines instead.
+fn strip_location(e: &serde_yml::Error) -> String {
+    let msg = e.to_string();
+    match msg.find(" at line ") {
+        Some(i) => msg[..i].to_string(),
+        None => msg,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(raw: &str) -> Vec<ProblemKind> {
+        check_content(raw, &Config::default())
+            .into_iter()
+            .map(|(_, k)| k)
+            .collect()
+    }
+
+    #[test]
+    fn valid_file_has_no_problems() {
+        let raw = "---\ntitle: ok\ncreated: 2026-02-20T14:30:52\nstatus: open\n---\n";
+        assert!(kinds(raw).is_empty());
+    }
+
+    #[test]
+    fn missing_delimiters() {
+        assert_eq!(kinds("title: foo\n"), vec![ProblemKind::MissingOpening]);
+        assert_eq!(
+            kinds("---\ntitle: foo\n"),
+            vec![ProblemKind::MissingClosing]
+        );
+    }
+
+    #[test]
+    fn invalid_status_reports_line() {
+        let raw = "---\ntitle: x\ncreated: 2026-02-20T14:30:52\nstatus: Done\n---\n";
+        let problems = check_content(raw, &Config::default());
+        let expected = Config::default().status_names();
+        assert_eq!(
+            problems,
+            vec![(
+                Some(4),
+                ProblemKind::InvalidStatus {
+                    value: "Done".to_string(),
+                    expected: expected.clone(),
+                }
+            )]
+        );
+        assert!(problems[0].1.is_fixable());
+        let closed = ProblemKind::InvalidStatus {
+            value: "closed".to_string(),
+            expected,
+        };
+        assert!(!closed.is_fixable());
+        assert_eq!(
+            closed.to_string(),
+            "invalid status 'closed' (expected open, done, cancelled)"
+        );
+    }
+
+    #[test]
+    fn bad_yaml_reports_file_line() {
+        let raw = "---\ntitle: [oops\ncreated: 2026-02-20T14:30:52\nstatus: open\n---\n";
+        let problems = check_content(raw, &Config::default());
+        assert_eq!(problems.len(), 1);
+        assert!(matches!(problems[0].1, ProblemKind::InvalidYaml(_)));
+        assert!(problems[0].0.is_some_and(|l| l >= 2));
+    }
+
+    #[test]
+    fn missing_field_is_invalid_frontmatter() {
+        let raw = "---\ntitle: x\nstatus: open\n---\n";
+        assert!(matches!(
+            kinds(raw).as_slice(),
+            [ProblemKind::InvalidFrontmatter(_)]
+        ));
+    }
+
+    #[test]
+    fn done_at_on_open_todo() {
+        let raw = "---\ntitle: x\ncreated: 2026-02-20T14:30:52\nstatus: open\ndone_at: 2026-02-21T10:00:00\n---\n";
+        assert_eq!(
+            check_content(raw, &Config::default()),
+            vec![(Some(5), ProblemKind::DoneAtOnOpen)]
+        );
+    }
+
+    #[test]
+    fn done_at_on_closed_todo_is_fine() {
+        let config: Config = serde_yml::from_str(
+            "statuses:\n  - name: wontfix\n    open: false\n  - name: review\n",
+        )
+        .unwrap();
+        let with_status = |status: &str| {
+            format!(
+                "---\ntitle: x\ncreated: 2026-02-20T14:30:52\nstatus: {status}\ndone_at: 2026-02-21T10:00:00\n---\n"
+            )
+        };
+        assert!(check_content(&with_status("cancelled"), &config).is_empty());
+        assert!(check_content(&with_status("wontfix"), &config).is_empty());
+        assert_eq!(
+            check_content(&with_status("review"), &config),
+            vec![(Some(5), ProblemKind::DoneAtOnOpen)]
+        );
+    }
+
+    #[test]
+    fn crlf_is_reported_alongside_other_problems() {
+        let raw = "---\r\ntitle: x\r\ncreated: 2026-02-20T14:30:52\r\nstatus: open\r\n---\r\n";
+        assert_eq!(kinds(raw), vec![ProblemKind::CrlfLineEndings]);
+    }
+}
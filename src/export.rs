@@ -0,0 +1,293 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use jiff::civil::DateTime;
+use jiff::tz::TimeZone;
+
+use crate::filter::Filter;
+use crate::storage::Store;
+use crate::todo::{Status, Todo};
+use crate::util::{format_duration, now};
+
+/// Formats `tdo export` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// todo.txt, one task per line
+    Todotxt,
+    /// CSV with a header row
+    Csv,
+    /// A Markdown checklist
+    Markdown,
+    /// iCalendar with one VTODO per todo
+    Ics,
+}
+
+/// Render `todos` in `format`. `now` is the `DTSTAMP` of iCalendar entries.
+pub fn render(format: ExportFormat, todos: &[&Todo], now: DateTime) -> Result<String> {
+    let mut out = String::new();
+    match format {
+        ExportFormat::Todotxt => {
+            for todo in todos {
+                out.push_str(&todotxt_line(todo));
+                out.push('\n');
+            }
+        }
+        ExportFormat::Csv => {
+            out.push_str(
+                "id,title,status,created,assigned,tags,due,estimate,done_at,cancelled_at\n",
+            );
+            for todo in todos {
+                let fm = &todo.frontmatter;
+                let fields = [
+                    todo.id.clone(),
+                    todo.title().to_string(),
+                    fm.status.to_string(),
+                    fm.created.to_string(),
+                    todo.assignees(),
+                    fm.tags.join(" "),
+                    fm.due.map(|d| d.to_string()).unwrap_or_default(),
+                    fm.estimate.map(format_duration).unwrap_or_default(),
+                    fm.done_at.map(|d| d.to_string()).unwrap_or_default(),
+                    fm.cancelled_at.map(|d| d.to_string()).unwrap_or_default(),
+                ];
+                let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                out.push_str(&fields.join(","));
+                out.push('\n');
+            }
+        }
+        ExportFormat::Markdown => {
+            for todo in todos {
+                out.push_str(&markdown_line(todo));
+                out.push('\n');
+            }
+        }
+        ExportFormat::Ics => render_ics(&mut out, todos, now)?,
+    }
+    Ok(out)
+}
+
+/// `x 2026-02-03 2026-01-20 title +tag due:2026-03-01`. Workflow states other
+/// than `open` and `done` are kept as `status:<state>`.
+fn todotxt_line(todo: &Todo) -> String {
+    let fm = &todo.frontmatter;
+    let mut line = String::new();
+    let closed_at = fm.done_at.or(fm.cancelled_at);
+    if !todo.is_open() {
+        line.push_str("x ");
+        if let Some(at) = closed_at {
+            write!(line, "{} ", at.date()).unwrap();
+        }
+    }
+    write!(line, "{} {}", fm.created.date(), todo.title()).unwrap();
+    for tag in &fm.tags {
+        let tag = format!("+{tag}");
+        if !todo.title().split_whitespace().any(|w| w == tag) {
+            write!(line, " {tag}").unwrap();
+        }
+    }
+    if let Some(due) = fm.due {
+        write!(line, " due:{due}").unwrap();
+    }
+    if !matches!(fm.status, Status::Open | Status::Done) {
+        write!(line, " status:{}", fm.status).unwrap();
+    }
+    line
+}
+
+/// `- [ ] title (assigned: alice) · due 2026-03-01`; cancelled todos are
+/// ticked and struck through.
+fn markdown_line(todo: &Todo) -> String {
+    let fm = &todo.frontmatter;
+    let mut line = match fm.status {
+        Status::Done => format!("- [x] {}", todo.title()),
+        Status::Cancelled => format!("- [x] ~~{}~~", todo.title()),
+        Status::Open => format!("- [ ] {}", todo.title()),
+        ref status => format!("- [ ] **{status}:** {}", todo.title()),
+    };
+    line.push_str(&todo.assigned_suffix());
+    if let Some(due) = fm.due
+        && todo.is_open()
+    {
+        write!(line, " · due {due}").unwrap();
+    }
+    line
+}
+
+/// Quote a CSV field when it contains a separator, quote or newline.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn render_ics(out: &mut String, todos: &[&Todo], now: DateTime) -> Result<()> {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:-//tdo//tdo {}//EN", env!("CARGO_PKG_VERSION")),
+    ];
+    let stamp = ics_time(now)?;
+    for todo in todos {
+        let fm = &todo.frontmatter;
+        lines.push("BEGIN:VTODO".to_string());
+        // IDs are short and random, so the creation time makes the UID
+        // unique across stores.
+        lines.push(format!(
+            "UID:{}-{}@tdo",
+            todo.id,
+            fm.created.strftime("%Y%m%dT%H%M%S")
+        ));
+        lines.push(format!("DTSTAMP:{stamp}"));
+        lines.push(format!("CREATED:{}", ics_time(fm.created)?));
+        lines.push(format!("SUMMARY:{}", ics_text(todo.title())));
+        if let Some(body) = &todo.body {
+            lines.push(format!("DESCRIPTION:{}", ics_text(body.trim_end())));
+        }
+        let status = match fm.status {
+            Status::Open => "NEEDS-ACTION",
+            Status::Done => "COMPLETED",
+            Status::Cancelled => "CANCELLED",
+            Status::Custom { open: true, .. } => "IN-PROCESS",
+            Status::Custom { open: false, .. } => "CANCELLED",
+        };
+        lines.push(format!("STATUS:{status}"));
+        if let Some(done_at) = fm.done_at {
+            lines.push(format!("COMPLETED:{}", ics_time(done_at)?));
+        }
+        if let Some(due) = fm.due {
+            lines.push(format!("DUE;VALUE=DATE:{}", due.strftime("%Y%m%d")));
+        }
+        if !fm.tags.is_empty() {
+            let tags: Vec<String> = fm.tags.iter().map(|t| ics_text(t)).collect();
+            lines.push(format!("CATEGORIES:{}", tags.join(",")));
+        }
+        lines.push("END:VTODO".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    for line in &lines {
+        out.push_str(&fold_ics_line(line));
+        out.push_str("\r\n");
+    }
+    Ok(())
+}
+
+/// A local timestamp as iCalendar UTC time, e.g. `20260220T133052Z`.
+fn ics_time(at: DateTime) -> Result<String> {
+    let utc = at.to_zoned(TimeZone::system())?.timestamp();
+    Ok(utc.strftime("%Y%m%dT%H%M%SZ").to_string())
+}
+
+/// Escape an iCalendar TEXT value.
+fn ics_text(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line into 75-octet pieces, continuing with a space, as
+/// RFC 5545 requires. Never splits a UTF-8 character.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+/// Write the todos `filter` selects, snoozed ones always included, to
+/// `output` or stdout.
+pub fn run_export(
+    store: &Store,
+    format: ExportFormat,
+    mut filter: Filter,
+    output: Option<&Path>,
+) -> Result<()> {
+    filter.include_snoozed = true;
+    let todos = filter.apply(store.list_all(), now().date());
+    let rendered = render(format, &todos, now())?;
+    match output {
+        Some(path) => {
+            fs::write(path, &rendered)
+                .with_context(|| format!("failed to write: {}", path.display()))?;
+            eprintln!("exported {} todo(s) to {}", todos.len(), path.display());
+        }
+        None => io::stdout().lock().write_all(rendered.as_bytes())?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::new_frontmatter;
+    use crate::todo::Frontmatter;
+
+    fn todo(id: &str, title: &str, status: Status) -> Todo {
+        let created = jiff::civil::date(2026, 1, 20).at(9, 30, 0, 0);
+        Todo {
+            id: id.to_string(),
+            filename: format!("{id}.md"),
+            frontmatter: Frontmatter {
+                status,
+                ..new_frontmatter(title, created, None)
+            },
+            body: None,
+        }
+    }
+
+    #[test]
+    fn todotxt_and_markdown() {
+        let mut open = todo("a3f9", "ship it", Status::Open);
+        open.frontmatter.tags = vec!["release".to_string()];
+        open.frontmatter.due = Some(jiff::civil::date(2026, 3, 1));
+        let mut done = todo("b4e1", "write, docs", Status::Done);
+        done.frontmatter.done_at = Some(jiff::civil::date(2026, 2, 3).at(8, 0, 0, 0));
+        let now = jiff::civil::date(2026, 2, 5).at(0, 0, 0, 0);
+
+        let txt = render(ExportFormat::Todotxt, &[&open, &done], now).unwrap();
+        assert_eq!(
+            txt,
+            "2026-01-20 ship it +release due:2026-03-01\nx 2026-02-03 2026-01-20 write, docs\n"
+        );
+        let md = render(ExportFormat::Markdown, &[&open, &done], now).unwrap();
+        assert_eq!(md, "- [ ] ship it · due 2026-03-01\n- [x] write, docs\n");
+        let csv = render(ExportFormat::Csv, &[&done], now).unwrap();
+        assert!(
+            csv.ends_with(
+                "b4e1,\"write, docs\",done,2026-01-20T09:30:00,,,,,2026-02-03T08:00:00,\n"
+            )
+        );
+    }
+
+    #[test]
+    fn ics_escapes_and_folds() {
+        let mut open = todo(
+            "a3f9",
+            &"long title; with, separators ".repeat(4),
+            Status::Open,
+        );
+        open.body = Some("line one\nline two\n".to_string());
+        let now = jiff::civil::date(2026, 2, 5).at(0, 0, 0, 0);
+        let ics = render(ExportFormat::Ics, &[&open], now).unwrap();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.contains("SUMMARY:long title\\; with\\, separators"));
+        assert!(ics.contains("DESCRIPTION:line one\\nline two\r\n"));
+        assert!(ics.contains("STATUS:NEEDS-ACTION\r\n"));
+        assert!(ics.lines().all(|l| l.len() <= 76));
+        assert!(ics.contains("\r\n "));
+    }
+}
@@ -0,0 +1,207 @@
+use clap::ValueEnum;
+use jiff::civil::{Date, DateTime};
+
+use crate::todo::{Status, Todo};
+
+/// Which todos to show and in what order, shared by `tdo list`, `count`,
+/// `export` and the TUI. The default is the open, unsnoozed todos oldest
+/// first, as plain `tdo list` prints them.
+#[derive(Debug, Default, Clone)]
+pub struct Filter {
+    /// Include done, cancelled and snoozed todos.
+    pub all: bool,
+    pub include_snoozed: bool,
+    /// Only todos in one of these states. Asking for a closed state shows
+    /// closed todos without `all`.
+    pub status: Vec<Status>,
+    pub assignee: AssigneeFilter,
+    /// Only todos created at or after this time.
+    pub created_after: Option<DateTime>,
+    /// Only todos created before this time.
+    pub created_before: Option<DateTime>,
+    /// Only todos done at or after this time; shows done todos without
+    /// `all`.
+    pub done_since: Option<DateTime>,
+    /// Only todos whose title or body contains this, ignoring case.
+    pub grep: Option<String>,
+    pub sort: SortKey,
+    pub reverse: bool,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub enum AssigneeFilter {
+    #[default]
+    Any,
+    Unassigned,
+    /// Assigned to anyone.
+    Assigned,
+    Assignee(String),
+}
+
+impl AssigneeFilter {
+    pub fn matches(&self, todo: &Todo) -> bool {
+        match self {
+            AssigneeFilter::Any => true,
+            AssigneeFilter::Unassigned => !todo.is_assigned(),
+            AssigneeFilter::Assigned => todo.is_assigned(),
+            AssigneeFilter::Assignee(name) => todo.is_assigned_to(name),
+        }
+    }
+}
+
+/// Orders for `--sort`. Ties keep creation order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortKey {
+    #[default]
+    Created,
+    /// When the todo was done; todos that aren't come last
+    #[value(name = "done_at", alias = "done-at")]
+    DoneAt,
+    Title,
+    Id,
+}
+
+impl Filter {
+    /// Whether `todo` passes every condition (`today` decides snoozing).
+    pub fn matches(&self, todo: &Todo, today: Date) -> bool {
+        let fm = &todo.frontmatter;
+        let closed_wanted = self.all || !self.status.is_empty() || self.done_since.is_some();
+        if !todo.is_open() && !closed_wanted {
+            return false;
+        }
+        if todo.is_open() && todo.is_snoozed(today) && !(self.all || self.include_snoozed) {
+            return false;
+        }
+        if !self.status.is_empty() && !self.status.contains(&fm.status) {
+            return false;
+        }
+        if !self.assignee.matches(todo) {
+            return false;
+        }
+        if self.created_after.is_some_and(|after| fm.created < after)
+            || self
+                .created_before
+                .is_some_and(|before| fm.created >= before)
+        {
+            return false;
+        }
+        if let Some(since) = self.done_since
+            && fm.done_at.is_none_or(|at| at < since)
+        {
+            return false;
+        }
+        if let Some(pattern) = &self.grep {
+            let pattern = pattern.to_lowercase();
+            let found = |text: &str| text.to_lowercase().contains(&pattern);
+            if !found(todo.title()) && !todo.body.as_deref().is_some_and(found) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// The matching todos from `todos` (in creation order, as the store
+    /// keeps them), sorted and limited.
+    pub fn apply<'a>(&self, todos: &'a [Todo], today: Date) -> Vec<&'a Todo> {
+        let mut matched: Vec<&Todo> = todos.iter().filter(|t| self.matches(t, today)).collect();
+        match self.sort {
+            SortKey::Created => matched.sort_by_key(|t| t.frontmatter.created),
+            SortKey::DoneAt => matched.sort_by_key(|t| {
+                let done_at = t.frontmatter.done_at;
+                (done_at.is_none(), done_at)
+            }),
+            SortKey::Title => matched.sort_by_cached_key(|t| t.title().to_lowercase()),
+            SortKey::Id => matched.sort_by(|a, b| a.id.cmp(&b.id)),
+        }
+        if self.reverse {
+            matched.reverse();
+        }
+        if let Some(limit) = self.limit {
+            matched.truncate(limit);
+        }
+        matched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::ToSpan;
+
+    use super::*;
+    use crate::ops::new_frontmatter;
+    use crate::todo::Frontmatter;
+
+    fn todo(id: &str, title: &str, day: i8, status: Status) -> Todo {
+        let created = jiff::civil::date(2026, 2, day).at(9, 0, 0, 0);
+        Todo {
+            id: id.to_string(),
+            filename: format!("{id}.md"),
+            frontmatter: Frontmatter {
+                done_at: (status == Status::Done).then(|| created.saturating_add(1.hour())),
+                status,
+                ..new_frontmatter(title, created, None)
+            },
+            body: None,
+        }
+    }
+
+    fn ids(todos: Vec<&Todo>) -> Vec<&str> {
+        todos.into_iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn closed_todos_only_when_asked_for() {
+        let todos = [
+            todo("a1", "write docs", 1, Status::Open),
+            todo("b2", "ship", 2, Status::Done),
+            todo("c3", "Write tests", 3, Status::Done),
+        ];
+        let today = jiff::civil::date(2026, 2, 10);
+        assert_eq!(ids(Filter::default().apply(&todos, today)), ["a1"]);
+
+        let done = Filter {
+            status: vec![Status::Done],
+            ..Filter::default()
+        };
+        assert_eq!(ids(done.apply(&todos, today)), ["b2", "c3"]);
+
+        let grep = Filter {
+            all: true,
+            grep: Some("WRITE".to_string()),
+            ..Filter::default()
+        };
+        assert_eq!(ids(grep.apply(&todos, today)), ["a1", "c3"]);
+
+        let since = Filter {
+            done_since: Some(jiff::civil::date(2026, 2, 3).at(0, 0, 0, 0)),
+            ..Filter::default()
+        };
+        assert_eq!(ids(since.apply(&todos, today)), ["c3"]);
+    }
+
+    #[test]
+    fn sort_reverse_and_limit() {
+        let mut todos = [
+            todo("c3", "banana", 1, Status::Done),
+            todo("a1", "Cherry", 2, Status::Open),
+            todo("b2", "apple", 3, Status::Done),
+        ];
+        todos[0].frontmatter.done_at = Some(jiff::civil::date(2026, 2, 9).at(0, 0, 0, 0));
+        let today = jiff::civil::date(2026, 2, 10);
+        let sorted = |sort, reverse, limit| {
+            let filter = Filter {
+                all: true,
+                sort,
+                reverse,
+                limit,
+                ..Filter::default()
+            };
+            ids(filter.apply(&todos, today))
+        };
+        assert_eq!(sorted(SortKey::Title, false, None), ["b2", "c3", "a1"]);
+        assert_eq!(sorted(SortKey::Id, true, Some(2)), ["c3", "b2"]);
+        // Todos that aren't done come last.
+        assert_eq!(sorted(SortKey::DoneAt, false, None), ["b2", "c3", "a1"]);
+    }
+}
@@ -0,0 +1,73 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+use anyhow::{Context, Result, bail};
+
+use crate::storage::LOCK_FILE;
+
+/// Run `git <args>` with `dir` as the working directory.
+fn git(dir: &Path, args: &[&str]) -> Result<Output> {
+    Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .context("failed to run git: is it installed?")
+}
+
+/// Run git and fail with its stderr if it exits non-zero.
+fn git_ok(dir: &Path, args: &[&str]) -> Result<Output> {
+    let output = git(dir, args)?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output)
+}
+
+/// Whether `dir` is inside a git work tree.
+pub fn is_repo(dir: &Path) -> bool {
+    git(dir, &["rev-parse", "--is-inside-work-tree"]).is_ok_and(|o| o.status.success())
+}
+
+/// `git config user.name`, as seen from `dir`.
+pub fn user_name(dir: &Path) -> Option<String> {
+    let output = git(dir, &["config", "user.name"]).ok()?;
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !name.is_empty()).then_some(name)
+}
+
+/// Stage everything in `dir` (except the lock file) and commit it with
+/// `message`. Only paths inside `dir` are committed, so unrelated staged
+/// changes elsewhere in the repository are left alone. Does nothing if the
+/// directory has no changes.
+pub fn commit_dir(dir: &Path, message: &str) -> Result<()> {
+    if !is_repo(dir) {
+        bail!("{} is not inside a git repository", dir.display());
+    }
+    let exclude_lock = format!(":(exclude){LOCK_FILE}");
+    git_ok(dir, &["add", "--all", "--", ".", &exclude_lock])?;
+    let staged = git(dir, &["diff", "--cached", "--quiet", "--", "."])?;
+    if staged.status.success() {
+        return Ok(());
+    }
+    git_ok(dir, &["commit", "--quiet", "--message", message, "--", "."])?;
+    Ok(())
+}
+
+/// Pull with rebase, stashing any uncommitted changes around it.
+pub fn pull_rebase(dir: &Path) -> Result<()> {
+    if !is_repo(dir) {
+        bail!("{} is not inside a git repository", dir.display());
+    }
+    git_ok(dir, &["pull", "--rebase", "--autostash", "--quiet"])?;
+    Ok(())
+}
+
+/// Push the current branch to its upstream.
+pub fn push(dir: &Path) -> Result<()> {
+    git_ok(dir, &["push", "--quiet"])?;
+    Ok(())
+}
@@ -0,0 +1,491 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use jiff::civil::{Date, DateTime};
+use jiff::tz::TimeZone;
+use jiff::{Timestamp, Zoned};
+use serde::Deserialize;
+
+use crate::ops::{new_frontmatter, parse_tags};
+use crate::storage::{Store, title_key};
+use crate::todo::{Event, EventKind, Frontmatter, Status};
+use crate::util::now;
+
+/// Export formats `tdo import` understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    /// todo.txt, one task per line
+    Todotxt,
+    /// `task export` output
+    TaskwarriorJson,
+    /// `gh issue list --json ...` or the REST API's issue list
+    GithubIssuesJson,
+}
+
+/// One todo read from an export, before it becomes a `Frontmatter`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    pub title: String,
+    pub created: Option<DateTime>,
+    pub status: Status,
+    /// When the item was done or cancelled.
+    pub closed_at: Option<DateTime>,
+    pub assigned: Vec<String>,
+    pub tags: Vec<String>,
+    pub due: Option<Date>,
+    pub deferred_until: Option<Date>,
+    pub body: Option<String>,
+    /// Where it came from, recorded on the `created` event.
+    pub source: String,
+}
+
+impl Item {
+    fn new(title: &str, source: String) -> Item {
+        Item {
+            title: title.trim().to_string(),
+            created: None,
+            status: Status::Open,
+            closed_at: None,
+            assigned: vec![],
+            tags: vec![],
+            due: None,
+            deferred_until: None,
+            body: None,
+            source,
+        }
+    }
+
+    /// The todo's frontmatter, with history events for the assignment and
+    /// the closing. `now` stands in for unknown dates.
+    ///
+    /// Closed items get `done_at`/`cancelled_at` set to the import time,
+    /// like `assigned_at`, so GC doesn't delete old ones on the next list;
+    /// the closing event keeps the original date.
+    pub fn frontmatter(&self, now: DateTime) -> Result<Frontmatter> {
+        let created = self.created.unwrap_or(now);
+        let mut fm = Frontmatter {
+            status: self.status.clone(),
+            tags: parse_tags(&self.tags)?,
+            due: self.due,
+            deferred_until: self.deferred_until,
+            ..new_frontmatter(&self.title, created, Some(self.source.clone()))
+        };
+        if !self.assigned.is_empty() {
+            // Assigned as of the import, so old items don't show up stale.
+            fm.assigned = self.assigned.clone();
+            fm.assigned_at = Some(now);
+            fm.history.push(Event {
+                at: now,
+                event: EventKind::Assigned,
+                detail: Some(self.assigned.join(", ")),
+            });
+        }
+        match self.status {
+            Status::Done => fm.done_at = Some(now),
+            Status::Cancelled => fm.cancelled_at = Some(now),
+            _ => return Ok(fm),
+        }
+        let event = if self.status == Status::Done {
+            EventKind::Done
+        } else {
+            EventKind::Cancelled
+        };
+        fm.history.push(Event {
+            at: self.closed_at.unwrap_or(now),
+            event,
+            detail: None,
+        });
+        Ok(fm)
+    }
+}
+
+/// Parse an export in `format` into items, in file order.
+pub fn parse(format: ImportFormat, raw: &str) -> Result<Vec<Item>> {
+    match format {
+        ImportFormat::Todotxt => Ok(raw.lines().filter_map(parse_todotxt_line).collect()),
+        ImportFormat::TaskwarriorJson => parse_taskwarrior(raw),
+        ImportFormat::GithubIssuesJson => parse_github_issues(raw),
+    }
+}
+
+/// Parse a todo.txt line: `x 2026-02-03 2026-01-20 (A) title +project @context due:2026-02-10`.
+/// Completion and creation dates become `closed_at` and `created`, projects
+/// and contexts become tags, `due:` the due date. The priority is dropped.
+fn parse_todotxt_line(line: &str) -> Option<Item> {
+    let mut rest = line.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    let done = rest.starts_with("x ");
+    if done {
+        rest = &rest[2..];
+    }
+    let mut dates = vec![];
+    loop {
+        rest = rest.trim_start();
+        if !done && dates.is_empty() && rest.len() > 3 {
+            let bytes = rest.as_bytes();
+            if bytes[0] == b'('
+                && bytes[1].is_ascii_uppercase()
+                && bytes[2] == b')'
+                && bytes[3] == b' '
+            {
+                rest = &rest[4..];
+                continue;
+            }
+        }
+        let Some(date) = rest.get(..10).and_then(|s| s.parse::<Date>().ok()) else {
+            break;
+        };
+        dates.push(date);
+        rest = &rest[10..];
+        // Done tasks have a completion date and then a creation date.
+        if dates.len() == if done { 2 } else { 1 } {
+            break;
+        }
+    }
+
+    let mut words = vec![];
+    let mut tags = vec![];
+    let mut due = None;
+    for word in rest.split_whitespace() {
+        if let Some(date) = word.strip_prefix("due:").and_then(|d| d.parse().ok()) {
+            due = Some(date);
+            continue;
+        }
+        if let Some(tag) = word.strip_prefix('+').or_else(|| word.strip_prefix('@'))
+            && !tag.is_empty()
+        {
+            tags.push(tag.to_string());
+        }
+        words.push(word);
+    }
+
+    let mut item = Item::new(&words.join(" "), "imported from todo.txt".to_string());
+    item.tags = tags;
+    item.due = due;
+    let midnight = |d: Date| d.to_datetime(jiff::civil::Time::midnight());
+    if done {
+        item.status = Status::Done;
+        item.closed_at = dates.first().copied().map(midnight);
+        item.created = dates.get(1).copied().map(midnight);
+    } else {
+        item.created = dates.first().copied().map(midnight);
+    }
+    Some(item)
+}
+
+#[derive(Deserialize)]
+struct TaskwarriorTask {
+    #[serde(default)]
+    uuid: String,
+    description: String,
+    status: String,
+    entry: Option<String>,
+    end: Option<String>,
+    due: Option<String>,
+    wait: Option<String>,
+    project: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    annotations: Vec<TaskwarriorAnnotation>,
+}
+
+#[derive(Deserialize)]
+struct TaskwarriorAnnotation {
+    description: String,
+}
+
+/// Parse `task export` output. Completed tasks become done, deleted ones
+/// cancelled; recurring templates are skipped since their instances are
+/// exported too. Annotations become the body.
+fn parse_taskwarrior(raw: &str) -> Result<Vec<Item>> {
+    let tasks: Vec<TaskwarriorTask> =
+        serde_json::from_str(raw).context("invalid Taskwarrior export")?;
+    let mut items = vec![];
+    for task in tasks {
+        let status = match task.status.as_str() {
+            "pending" | "waiting" => Status::Open,
+            "completed" => Status::Done,
+            "deleted" => Status::Cancelled,
+            "recurring" => continue,
+            other => bail!("unknown Taskwarrior status '{other}'"),
+        };
+        let source = match task.uuid.split('-').next() {
+            Some(short) if !short.is_empty() => format!("imported from taskwarrior {short}"),
+            _ => "imported from taskwarrior".to_string(),
+        };
+        let mut item = Item::new(&task.description, source);
+        item.status = status;
+        item.created = task
+            .entry
+            .as_deref()
+            .map(parse_taskwarrior_date)
+            .transpose()?;
+        item.closed_at = task
+            .end
+            .as_deref()
+            .map(parse_taskwarrior_date)
+            .transpose()?;
+        item.due = task
+            .due
+            .as_deref()
+            .map(parse_taskwarrior_date)
+            .transpose()?
+            .map(|d| d.date());
+        item.deferred_until = task
+            .wait
+            .as_deref()
+            .map(parse_taskwarrior_date)
+            .transpose()?
+            .map(|d| d.date());
+        item.tags = task.project.into_iter().chain(task.tags).collect();
+        if !task.annotations.is_empty() {
+            let notes: Vec<String> = task
+                .annotations
+                .iter()
+                .map(|a| format!("- {}\n", a.description))
+                .collect();
+            item.body = Some(notes.concat());
+        }
+        items.push(item);
+    }
+    Ok(items)
+}
+
+/// Taskwarrior writes UTC timestamps as `20260120T093000Z`.
+fn parse_taskwarrior_date(s: &str) -> Result<DateTime> {
+    let utc = DateTime::strptime("%Y%m%dT%H%M%SZ", s)
+        .with_context(|| format!("invalid Taskwarrior date '{s}'"))?;
+    Ok(local(utc.to_zoned(TimeZone::UTC)?.timestamp()))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GithubIssue {
+    number: Option<u64>,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    #[serde(alias = "created_at")]
+    created_at: Option<Timestamp>,
+    #[serde(alias = "closed_at")]
+    closed_at: Option<Timestamp>,
+    #[serde(alias = "state_reason")]
+    state_reason: Option<String>,
+    #[serde(default)]
+    assignees: Vec<GithubUser>,
+    #[serde(default)]
+    labels: Vec<GithubLabel>,
+    /// Set by the REST API on pull requests, which it lists as issues.
+    #[serde(rename = "pull_request")]
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct GithubUser {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct GithubLabel {
+    name: String,
+}
+
+/// Parse a list of issues from `gh issue list --json` (camelCase keys) or
+/// the REST API (snake_case keys). Issues closed as not planned are
+/// cancelled; labels with spaces are turned into dashed tags.
+fn parse_github_issues(raw: &str) -> Result<Vec<Item>> {
+    let issues: Vec<GithubIssue> =
+        serde_json::from_str(raw).context("invalid GitHub issues export")?;
+    let mut items = vec![];
+    for issue in issues {
+        if issue.pull_request.is_some() {
+            continue;
+        }
+        let source = match issue.number {
+            Some(number) => format!("imported from github #{number}"),
+            None => "imported from github".to_string(),
+        };
+        let mut item = Item::new(&issue.title, source);
+        item.status = match issue.state.to_lowercase().as_str() {
+            "open" => Status::Open,
+            "closed"
+                if issue
+                    .state_reason
+                    .is_some_and(|r| r.eq_ignore_ascii_case("not_planned")) =>
+            {
+                Status::Cancelled
+            }
+            "closed" => Status::Done,
+            other => bail!("unknown GitHub issue state '{other}'"),
+        };
+        item.created = issue.created_at.map(local);
+        item.closed_at = issue.closed_at.map(local);
+        item.assigned = issue.assignees.into_iter().map(|u| u.login).collect();
+        item.tags = issue
+            .labels
+            .into_iter()
+            .map(|l| {
+                l.name
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|word| !word.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("-")
+            })
+            .collect();
+        item.body = issue
+            .body
+            .filter(|b| !b.trim().is_empty())
+            .map(|b| format!("{}\n", b.replace("\r\n", "\n").trim_end()));
+        items.push(item);
+    }
+    Ok(items)
+}
+
+fn local(timestamp: Timestamp) -> DateTime {
+    Zoned::new(timestamp, TimeZone::system()).datetime()
+}
+
+/// Import the items in `path`. Items whose title matches an existing todo
+/// (or an earlier item) are skipped, as are closed items unless `all` is
+/// set. With `dry_run` only prints what would be imported.
+pub fn run_import(
+    store: &mut Store,
+    format: ImportFormat,
+    path: &Path,
+    all: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("failed to read: {}", path.display()))?;
+    let items = parse(format, &raw).with_context(|| format!("{}", path.display()))?;
+
+    let mut seen: HashSet<String> = store
+        .list_all()
+        .iter()
+        .map(|t| title_key(t.title()))
+        .collect();
+    // Build every todo before writing any, so a bad item doesn't leave
+    // half an import behind.
+    let now = now();
+    let mut todos = vec![];
+    let mut skipped = 0;
+    for item in &items {
+        if item.title.is_empty() {
+            eprintln!("skipped: item without a title ({})", item.source);
+            skipped += 1;
+            continue;
+        }
+        if !all && !item.status.is_open() {
+            skipped += 1;
+            continue;
+        }
+        if !seen.insert(title_key(&item.title)) {
+            eprintln!("skipped: {} (already exists)", item.title);
+            skipped += 1;
+            continue;
+        }
+        let fm = item
+            .frontmatter(now)
+            .with_context(|| format!("can't import '{}'", item.title))?;
+        todos.push((item, fm));
+    }
+    for (item, fm) in &todos {
+        if dry_run {
+            println!("{}  [{}]", item.title, item.status);
+        } else {
+            let id = store.create(fm, item.body.as_deref())?;
+            println!("{id}  {}", item.title);
+        }
+    }
+
+    let imported = todos.len();
+    let verb = if dry_run { "would import" } else { "imported" };
+    eprintln!("{verb} {imported} todo(s), skipped {skipped}");
+    if !dry_run && imported > 0 {
+        store.commit(&format!("tdo: import {imported} todo(s)"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn todotxt_lines() {
+        let items = parse(
+            ImportFormat::Todotxt,
+            "(A) 2026-01-20 call mom +family @phone due:2026-02-10\n\nx 2026-02-03 2026-01-02 file taxes\n",
+        )
+        .unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].title, "call mom +family @phone");
+        assert_eq!(items[0].tags, ["family", "phone"]);
+        assert_eq!(items[0].due, Some(jiff::civil::date(2026, 2, 10)));
+        assert_eq!(
+            items[0].created,
+            Some(jiff::civil::date(2026, 1, 20).at(0, 0, 0, 0))
+        );
+        assert_eq!(items[1].status, Status::Done);
+        assert_eq!(
+            items[1].closed_at,
+            Some(jiff::civil::date(2026, 2, 3).at(0, 0, 0, 0))
+        );
+        assert_eq!(
+            items[1].created,
+            Some(jiff::civil::date(2026, 1, 2).at(0, 0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn taskwarrior_export() {
+        let raw = r#"[
+            {"uuid": "8a1c2b3d-0000", "description": "write docs", "status": "pending",
+             "entry": "20260120T093000Z", "project": "site", "tags": ["writing"],
+             "annotations": [{"entry": "20260121T093000Z", "description": "see wiki"}]},
+            {"description": "old idea", "status": "deleted", "entry": "20260101T000000Z",
+             "end": "20260102T000000Z"},
+            {"description": "standup", "status": "recurring"}
+        ]"#;
+        let items = parse(ImportFormat::TaskwarriorJson, raw).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].tags, ["site", "writing"]);
+        assert_eq!(items[0].body.as_deref(), Some("- see wiki\n"));
+        assert_eq!(items[0].source, "imported from taskwarrior 8a1c2b3d");
+        assert!(items[0].created.is_some());
+        assert_eq!(items[1].status, Status::Cancelled);
+        assert!(items[1].closed_at.is_some());
+    }
+
+    #[test]
+    fn github_issues() {
+        let raw = r#"[
+            {"number": 12, "title": "Crash on start", "body": "Steps\r\n", "state": "OPEN",
+             "createdAt": "2026-01-20T09:30:00Z", "assignees": [{"login": "alice"}],
+             "labels": [{"name": "good first issue"}, {"name": "p1, urgent"}]},
+            {"number": 13, "title": "Dark mode", "state": "closed", "state_reason": "not_planned",
+             "created_at": "2026-01-20T09:30:00Z", "closed_at": "2026-01-22T09:30:00Z"},
+            {"number": 14, "title": "Fix typo", "state": "open", "pull_request": {}}
+        ]"#;
+        let items = parse(ImportFormat::GithubIssuesJson, raw).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].assigned, ["alice"]);
+        assert_eq!(items[0].tags, ["good-first-issue", "p1-urgent"]);
+        assert_eq!(items[0].body.as_deref(), Some("Steps\n"));
+        assert_eq!(items[1].status, Status::Cancelled);
+
+        let now = now();
+        let fm = items[1].frontmatter(now).unwrap();
+        assert_eq!(fm.cancelled_at, Some(now));
+        let closed = fm.history.last().unwrap();
+        assert_eq!(closed.event, EventKind::Cancelled);
+        assert_eq!(Some(closed.at), items[1].closed_at);
+    }
+}
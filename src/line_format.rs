@@ -0,0 +1,399 @@
+use anyhow::{Result, bail};
+use jiff::civil::{Date, DateTime};
+
+use crate::config::{Config, StatusColor};
+use crate::ops::{CYAN, DIM, MAGENTA, RESET, YELLOW, ansi_color};
+use crate::todo::Todo;
+use crate::util::format_duration;
+
+/// Placeholders a list template can use: every frontmatter field, plus the
+/// ID, filename and first body line.
+const FIELDS: [&str; 19] = [
+    "id",
+    "filename",
+    "title",
+    "status",
+    "created",
+    "assigned",
+    "assigned_at",
+    "done_at",
+    "cancelled_at",
+    "estimate",
+    "due",
+    "recur",
+    "previous",
+    "tags",
+    "deferred_until",
+    "work",
+    "comments",
+    "history",
+    "body",
+];
+
+/// Fields that can be shown relative to now with `:ago`.
+const TIME_FIELDS: [&str; 6] = [
+    "created",
+    "assigned_at",
+    "done_at",
+    "cancelled_at",
+    "due",
+    "deferred_until",
+];
+
+/// A parsed `tdo list --template`, e.g. `{id} {status:>6} {title:.40}
+/// {created:ago}`. `{{` and `}}` are literal braces.
+#[derive(Debug)]
+pub struct LineFormat {
+    pieces: Vec<Piece>,
+}
+
+#[derive(Debug)]
+enum Piece {
+    Text(String),
+    Field(Field),
+}
+
+/// `{name[:ago][:spec]}`, where spec is `[<>^][width][.max]` as in Rust's
+/// `format!`, except that `.max` truncates with an ellipsis.
+#[derive(Debug)]
+struct Field {
+    name: &'static str,
+    ago: bool,
+    align: Align,
+    width: usize,
+    max: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+impl LineFormat {
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut pieces = vec![];
+        let mut text = String::new();
+        let mut chars = template.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let Some(end) = rest.find('}') else {
+                        bail!("unclosed '{{' in list template");
+                    };
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(Piece::Field(Field::parse(&rest[..end])?));
+                    chars = rest[end + 1..].chars();
+                }
+                '}' => bail!("unmatched '}}' in list template (write '}}}}' for a brace)"),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        Ok(LineFormat { pieces })
+    }
+
+    /// Fill in the template for `todo`. With `color`, fields are colored as
+    /// in the default list (overdue due dates in red), and closed todos are
+    /// dimmed as a whole.
+    pub fn render(&self, todo: &Todo, config: &Config, now: DateTime, color: bool) -> String {
+        let dim_line = color && !todo.is_open();
+        let mut line = String::new();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Text(text) => line.push_str(text),
+                Piece::Field(field) => {
+                    let value = field.truncate(field.value(todo, now));
+                    let (left, right) = field.padding(&value);
+                    line.push_str(&" ".repeat(left));
+                    match field
+                        .color(todo, config, now)
+                        .filter(|_| color && !dim_line)
+                    {
+                        Some(code) if !value.is_empty() => {
+                            line.push_str(code);
+                            line.push_str(&value);
+                            line.push_str(RESET);
+                        }
+                        _ => line.push_str(&value),
+                    }
+                    line.push_str(&" ".repeat(right));
+                }
+            }
+        }
+        if dim_line {
+            format!("{DIM}{line}{RESET}")
+        } else {
+            line
+        }
+    }
+}
+
+impl Field {
+    fn parse(inner: &str) -> Result<Self> {
+        let mut parts = inner.split(':');
+        let name = parts.next().unwrap_or_default().trim();
+        let Some(name) = FIELDS.iter().copied().find(|f| *f == name) else {
+            bail!(
+                "unknown field '{{{name}}}' in list template (expected one of {})",
+                FIELDS.join(", ")
+            );
+        };
+        let mut field = Field {
+            name,
+            ago: false,
+            align: Align::Left,
+            width: 0,
+            max: None,
+        };
+        for part in parts {
+            if part == "ago" {
+                if !TIME_FIELDS.contains(&name) {
+                    bail!("'{{{name}:ago}}': only times can be relative");
+                }
+                field.ago = true;
+            } else if !field.parse_spec(part) {
+                bail!("invalid format '{part}' in '{{{inner}}}' (expected e.g. >6, <20 or .40)");
+            }
+        }
+        Ok(field)
+    }
+
+    /// Parse `[<>^][width][.max]` into `self`; false if it isn't one.
+    fn parse_spec(&mut self, spec: &str) -> bool {
+        let (align, rest) = match spec.chars().next() {
+            Some('<') => (Align::Left, &spec[1..]),
+            Some('>') => (Align::Right, &spec[1..]),
+            Some('^') => (Align::Center, &spec[1..]),
+            _ => (Align::Left, spec),
+        };
+        let (width, max) = match rest.split_once('.') {
+            Some((width, max)) => (width, Some(max)),
+            None => (rest, None),
+        };
+        if width.is_empty() && max.is_none() {
+            return false;
+        }
+        let Ok(width) = (if width.is_empty() {
+            Ok(0)
+        } else {
+            width.parse()
+        }) else {
+            return false;
+        };
+        let max = match max.map(str::parse) {
+            Some(Ok(max)) => Some(max),
+            Some(Err(_)) => return false,
+            None => None,
+        };
+        self.align = align;
+        self.width = width;
+        self.max = max;
+        true
+    }
+
+    fn value(&self, todo: &Todo, now: DateTime) -> String {
+        let fm = &todo.frontmatter;
+        let time = |at: Option<DateTime>| match at {
+            Some(at) if self.ago => relative(now, at),
+            Some(at) => at.strftime("%Y-%m-%d %H:%M").to_string(),
+            None => String::new(),
+        };
+        let date = |date: Option<Date>| match date {
+            Some(date) if self.ago => relative_date(now.date(), date),
+            Some(date) => date.to_string(),
+            None => String::new(),
+        };
+        match self.name {
+            "id" => todo.id.clone(),
+            "filename" => todo.filename.clone(),
+            "title" => todo.title().to_string(),
+            "status" => fm.status.to_string(),
+            "created" => time(Some(fm.created)),
+            "assigned" => todo.assignees(),
+            "assigned_at" => time(fm.assigned_at),
+            "done_at" => time(fm.done_at),
+            "cancelled_at" => time(fm.cancelled_at),
+            "estimate" => fm.estimate.map(format_duration).unwrap_or_default(),
+            "due" => date(fm.due),
+            "recur" => fm.recur.as_ref().map(|r| r.to_string()).unwrap_or_default(),
+            "previous" => fm.previous.clone().unwrap_or_default(),
+            "tags" => {
+                let tags: Vec<String> = fm.tags.iter().map(|t| format!("#{t}")).collect();
+                tags.join(" ")
+            }
+            "deferred_until" => date(fm.deferred_until),
+            "work" if fm.work.is_empty() => String::new(),
+            "work" => format_duration(todo.time_spent(now)),
+            "comments" => fm.comments.len().to_string(),
+            "history" => fm.history.len().to_string(),
+            "body" => todo
+                .body
+                .as_deref()
+                .and_then(|b| b.lines().map(str::trim).find(|l| !l.is_empty()))
+                .unwrap_or_default()
+                .to_string(),
+            _ => unreachable!("fields are checked when parsing"),
+        }
+    }
+
+    /// Cut `value` to `max` characters, ending in an ellipsis.
+    fn truncate(&self, value: String) -> String {
+        match self.max {
+            Some(max) if value.chars().count() > max => {
+                let mut cut: String = value.chars().take(max.saturating_sub(1)).collect();
+                if max > 0 {
+                    cut.push('…');
+                }
+                cut
+            }
+            _ => value,
+        }
+    }
+
+    /// Spaces before and after `value` to fill `width`.
+    fn padding(&self, value: &str) -> (usize, usize) {
+        let pad = self.width.saturating_sub(value.chars().count());
+        match self.align {
+            Align::Left => (0, pad),
+            Align::Right => (pad, 0),
+            Align::Center => (pad / 2, pad - pad / 2),
+        }
+    }
+
+    fn color(&self, todo: &Todo, config: &Config, now: DateTime) -> Option<&'static str> {
+        match self.name {
+            "id" => Some(CYAN),
+            "status" => config
+                .status_color(&todo.frontmatter.status)
+                .map(ansi_color),
+            "tags" | "deferred_until" => Some(DIM),
+            "assigned" => Some(MAGENTA),
+            "due" if todo.frontmatter.due.is_some_and(|due| due < now.date()) => {
+                Some(ansi_color(StatusColor::Red))
+            }
+            "due" => Some(YELLOW),
+            _ => None,
+        }
+    }
+}
+
+/// `at` relative to `now`: `just now`, `5m ago`, `in 3d`, in the largest
+/// whole unit up to years.
+fn relative(now: DateTime, at: DateTime) -> String {
+    let seconds = now.duration_since(at).as_secs();
+    if seconds.abs() < 60 {
+        return "just now".to_string();
+    }
+    let units = [
+        (365 * 86_400, "y"),
+        (30 * 86_400, "mo"),
+        (7 * 86_400, "w"),
+        (86_400, "d"),
+        (3_600, "h"),
+        (60, "m"),
+    ];
+    let (size, unit) = units
+        .into_iter()
+        .find(|(size, _)| seconds.abs() >= *size)
+        .unwrap_or((60, "m"));
+    let amount = seconds.abs() / size;
+    if seconds > 0 {
+        format!("{amount}{unit} ago")
+    } else {
+        format!("in {amount}{unit}")
+    }
+}
+
+/// `date` relative to `today`, in days or larger units: `today`, `2d ago`,
+/// `in 1w`.
+fn relative_date(today: Date, date: Date) -> String {
+    if date == today {
+        return "today".to_string();
+    }
+    let midnight = |d: Date| d.to_datetime(jiff::civil::Time::midnight());
+    relative(midnight(today), midnight(date))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::new_frontmatter;
+    use crate::todo::Frontmatter;
+
+    fn todo() -> Todo {
+        Todo {
+            id: "a3f9".to_string(),
+            filename: "a3f9-fix-login.md".to_string(),
+            frontmatter: Frontmatter {
+                assigned: vec!["alice".to_string()],
+                estimate: Some(jiff::SignedDuration::from_mins(90)),
+                due: Some(jiff::civil::date(2026, 2, 9)),
+                tags: vec!["auth".to_string(), "ui".to_string()],
+                ..new_frontmatter(
+                    "fix the login page",
+                    jiff::civil::date(2026, 2, 1).at(9, 0, 0, 0),
+                    None,
+                )
+            },
+            body: Some("\nSafari only.\nMore detail.\n".to_string()),
+        }
+    }
+
+    fn render(template: &str, color: bool) -> String {
+        let now = jiff::civil::date(2026, 2, 10).at(12, 0, 0, 0);
+        LineFormat::parse(template)
+            .unwrap()
+            .render(&todo(), &Config::default(), now, color)
+    }
+
+    #[test]
+    fn fields_widths_and_truncation() {
+        assert_eq!(
+            render("{id} {status:>6}|{title:<10.10}|{assigned:^7}|", false),
+            "a3f9   open|fix the l…| alice |"
+        );
+        assert_eq!(
+            render("{tags} {estimate} {body} {{x}}", false),
+            "#auth #ui 1h 30m Safari only. {x}"
+        );
+        assert_eq!(
+            render("{created:ago} / due {due:ago} ({due})", false),
+            "1w ago / due 1d ago (2026-02-09)"
+        );
+    }
+
+    #[test]
+    fn overdue_dates_are_red_and_padding_is_uncolored() {
+        let line = render("{id:<6}{due:ago}", true);
+        assert_eq!(line, "\x1b[36ma3f9\x1b[0m  \x1b[31m1d ago\x1b[0m");
+    }
+
+    #[test]
+    fn bad_templates_are_rejected() {
+        for (template, error) in [
+            ("{owner}", "unknown field '{owner}'"),
+            ("{title:ago}", "only times can be relative"),
+            ("{title:>x}", "invalid format '>x'"),
+            ("{title", "unclosed '{'"),
+            ("title}", "unmatched '}'"),
+        ] {
+            let err = LineFormat::parse(template).unwrap_err().to_string();
+            assert!(err.contains(error), "{template}: {err}");
+        }
+    }
+}
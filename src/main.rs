@@ -1,6 +1,18 @@
 mod cli;
+mod completions;
+mod config;
+mod doctor;
+mod export;
+mod filter;
+mod git;
+mod import;
+mod line_format;
+mod merge;
 mod ops;
+mod recur;
+mod report;
 mod storage;
+mod template;
 mod todo;
 mod tui;
 mod util;
@@ -9,13 +21,49 @@ use anyhow::Result;
 use clap::Parser;
 
 use cli::{Cli, Command};
+use line_format::LineFormat;
 use storage::Store;
 
 fn main() -> Result<()> {
+    // Answers the todo ID requests of the script `tdo completions` prints:
+    // `COMPLETE=ids tdo -- <words>`.
+    if std::env::var_os("COMPLETE").is_some_and(|v| v == "ids") {
+        let words: Vec<String> = std::env::args_os()
+            .skip(2)
+            .map(|w| w.to_string_lossy().into_owned())
+            .collect();
+        for candidate in completions::id_candidates(&words) {
+            println!("{candidate}");
+        }
+        return Ok(());
+    }
+
     let cli = Cli::parse();
     let is_tty = util::stdout_is_tty();
     let dir = Store::resolve_dir(cli.dir.as_deref());
-    let mut store = Store::open(&dir)?;
+    let mut command = cli::resolve_command(cli, is_tty);
+
+    if let Command::Completions(shell) = command {
+        completions::write_script(shell, &mut std::io::stdout())?;
+        return Ok(());
+    }
+
+    // The merge driver runs inside git on temporary files and must not
+    // touch (or lock) the store. It only reads the config, for statuses.
+    if let Command::MergeDriver { base, ours, theirs } = &command {
+        let config = config::Config::load(&dir).unwrap_or_default();
+        if !merge::run_merge_driver(base, ours, theirs, &config)? {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Claims from parallel agents queue up on the lock rather than failing.
+    let mut store = if matches!(command, Command::Claim { .. } | Command::Release { .. }) {
+        Store::open_waiting(&dir)?
+    } else {
+        Store::open(&dir)?
+    };
     if store.skipped > 0 {
         eprintln!(
             "warning: {} malformed todo file{} skipped",
@@ -23,48 +71,322 @@ fn main() -> Result<()> {
             if store.skipped == 1 { "" } else { "s" }
         );
     }
+    for files in &store.duplicates {
+        eprintln!(
+            "warning: duplicate id in {}; run `tdo reid <filename>` or `tdo doctor --fix`",
+            files.join(", ")
+        );
+    }
 
-    match cli::resolve_command(cli, is_tty) {
-        Command::Create { title, body } => {
-            let id = ops::create_todo(&mut store, &title, body.as_deref())?;
+    let action = command.action();
+    let reopening = matches!(command, Command::Reopen(_));
+    if let Some(id) = command.id_mut() {
+        match id {
+            Some(query) => *query = ops::resolve_id(&store, query, util::can_prompt())?,
+            None => {
+                if !(is_tty && util::can_prompt()) {
+                    anyhow::bail!("no todo given: pass its ID or title");
+                }
+                let (returned, picked) = tui::pick_todo(store, action, reopening)?;
+                store = returned;
+                match picked {
+                    Some(picked) => *id = Some(picked),
+                    None => {
+                        eprintln!("cancelled");
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    match command {
+        Command::Create {
+            title,
+            body,
+            body_file,
+            estimate,
+            due,
+            recur,
+            tags,
+            template,
+        } => {
+            let body = ops::read_body(body, body_file.as_deref())?;
+            let options = ops::CreateOptions {
+                estimate,
+                due,
+                recur,
+                tags,
+                template: template.or_else(|| store.config.default_template.clone()),
+            };
+            let id = ops::create_todo(&mut store, &title, body.as_deref(), &options)?;
             println!("{id}");
         }
-        Command::Edit { id, body } => {
+        Command::CreateFromStdin {
+            estimate,
+            due,
+            recur,
+            tags,
+        } => {
+            let defaults = ops::CreateOptions {
+                estimate,
+                due,
+                recur,
+                tags,
+                template: None,
+            };
+            let raw = std::io::read_to_string(std::io::stdin())?;
+            let todos = ops::parse_stdin_todos(&raw, &defaults)?;
+            let ids = ops::create_todos(&mut store, &todos)?;
+            for (id, todo) in ids.iter().zip(&todos) {
+                println!("{id}  {}", todo.title);
+            }
+            eprintln!("added {} todo(s)", ids.len());
+        }
+        Command::Edit {
+            id: Some(id),
+            body,
+            body_file,
+        } => {
+            let body = ops::read_body(body, body_file.as_deref())?;
             let interactive = is_tty && body.is_none();
             ops::edit_todo(&mut store, &id, body.as_deref(), interactive)?;
         }
-        Command::Done(id) => {
-            let todo = ops::mark_done(&mut store, &id)?;
+        Command::Done(Some(id)) => {
+            let (todo, next) = ops::mark_done(&mut store, &id)?;
             eprintln!("done: {}  {}", todo.id, todo.title());
+            if let Some(next) = next
+                && let Some(due) = next.frontmatter.due
+            {
+                eprintln!("next: {}  {} (due {due})", next.id, next.title());
+            }
         }
-        Command::Reopen(id) => {
+        Command::Reopen(Some(id)) => {
             let todo = ops::reopen_todo(&mut store, &id)?;
             eprintln!("reopened: {}  {}", todo.id, todo.title());
         }
-        Command::Delete { id, force } => match ops::delete_todo(&mut store, &id, is_tty, force)? {
+        Command::Delete {
+            id: Some(id),
+            force,
+        } => match ops::delete_todo(&mut store, &id, is_tty, force)? {
             Some(todo) => eprintln!("deleted: {}  {}", todo.id, todo.title()),
             None => eprintln!("cancelled"),
         },
-        Command::Assign { id, name } => {
-            let todo = ops::assign_todo(&mut store, &id, name.as_deref())?;
-            eprintln!("assigned: {}  {}", todo.id, todo.title());
-        }
-        Command::Unassign(id) => {
-            let todo = ops::unassign_todo(&mut store, &id)?;
-            eprintln!("unassigned: {}  {}", todo.id, todo.title());
-        }
-        Command::List { all } => ops::list_todos(&mut store, all)?,
-        Command::View(id) => ops::view_todo(&store, &id)?,
-        Command::Count { all } => {
-            let count = if all {
-                store.list_all().len()
-            } else {
-                store.list_open().len()
+        Command::Assign {
+            id: Some(id),
+            names,
+        } => {
+            let todo = ops::assign_todo(&mut store, &id, &names)?;
+            eprintln!(
+                "assigned: {}  {}{}",
+                todo.id,
+                todo.title(),
+                todo.assigned_suffix()
+            );
+        }
+        Command::Unassign {
+            id: Some(id),
+            names,
+        } => {
+            let todo = ops::unassign_todo(&mut store, &id, &names)?;
+            eprintln!(
+                "unassigned: {}  {}{}",
+                todo.id,
+                todo.title(),
+                todo.assigned_suffix()
+            );
+        }
+        Command::List { filter, template } => {
+            let filter = filter.into_filter(&store)?;
+            let format = list_format(&store, template)?;
+            ops::list_todos(&mut store, &filter, format.as_ref())?
+        }
+        Command::View {
+            id: Some(id),
+            history,
+        } => ops::view_todo(&store, &id, history)?,
+        Command::Log { limit } => ops::log_todos(&store, limit)?,
+        Command::Start(Some(id)) => {
+            let (todo, stopped) = ops::start_timer(&mut store, &id)?;
+            if let Some(stopped) = stopped {
+                eprintln!("stopped: {}  {}", stopped.id, stopped.title());
+            }
+            eprintln!("started: {}  {}", todo.id, todo.title());
+        }
+        Command::Stop(id) => {
+            let todo = ops::stop_timer(&mut store, id.as_deref())?;
+            let spent = todo
+                .frontmatter
+                .work
+                .last()
+                .map(|w| util::format_duration(w.duration(util::now())))
+                .unwrap_or_default();
+            eprintln!("stopped: {}  {} ({spent})", todo.id, todo.title());
+        }
+        Command::LogTime {
+            id: Some(id),
+            duration,
+        } => {
+            let todo = ops::log_time(&mut store, &id, &duration)?;
+            eprintln!(
+                "logged: {}  {} (total {})",
+                todo.id,
+                todo.title(),
+                util::format_duration(todo.time_spent(util::now()))
+            );
+        }
+        Command::Estimate {
+            id: Some(id),
+            duration,
+        } => {
+            let todo = ops::set_estimate(&mut store, &id, duration.as_deref())?;
+            match todo.frontmatter.estimate {
+                Some(estimate) => eprintln!(
+                    "estimate: {}  {} ({})",
+                    todo.id,
+                    todo.title(),
+                    util::format_duration(estimate)
+                ),
+                None => eprintln!("estimate cleared: {}  {}", todo.id, todo.title()),
+            }
+        }
+        Command::Tag { id: Some(id), tags } => {
+            let todo = ops::tag_todo(&mut store, &id, &tags)?;
+            eprintln!("tagged: {}  {}", todo.id, todo.title());
+        }
+        Command::Untag { id: Some(id), tags } => {
+            let todo = ops::untag_todo(&mut store, &id, &tags)?;
+            eprintln!("untagged: {}  {}", todo.id, todo.title());
+        }
+        Command::Claim { tags, name } => {
+            let Some(todo) = ops::claim_next(&mut store, &tags, name.as_deref())? else {
+                eprintln!("nothing to claim");
+                std::process::exit(ops::EXIT_NOTHING_TO_CLAIM);
             };
-            println!("{count}");
+            println!("{}  {}", todo.id, todo.title());
+            if let Some(body) = &todo.body {
+                println!();
+                print!("{body}");
+            }
+        }
+        Command::Release { id: Some(id), name } => {
+            let todo = ops::release_todo(&mut store, &id, name.as_deref())?;
+            eprintln!("released: {}  {}", todo.id, todo.title());
+        }
+        Command::Note {
+            id: Some(id),
+            text,
+            name,
+        } => {
+            let text = ops::read_body(Some(text), None)?.unwrap_or_default();
+            let todo = ops::add_comment(&mut store, &id, &text, name.as_deref())?;
+            eprintln!("noted: {}  {}", todo.id, todo.title());
+        }
+        Command::Stale => ops::list_stale(&mut store)?,
+        Command::Cancel {
+            id: Some(id),
+            reason,
+        } => {
+            let todo = ops::cancel_todo(&mut store, &id, reason.as_deref())?;
+            eprintln!("cancelled: {}  {}", todo.id, todo.title());
+        }
+        Command::Status {
+            id: Some(id),
+            state,
+        } => {
+            let (todo, next) = ops::set_status(&mut store, &id, &state)?;
+            eprintln!("{}: {}  {}", todo.frontmatter.status, todo.id, todo.title());
+            if let Some(next) = next
+                && let Some(due) = next.frontmatter.due
+            {
+                eprintln!("next: {}  {} (due {due})", next.id, next.title());
+            }
+        }
+        Command::Snooze {
+            id: Some(id),
+            until,
+        } => {
+            let todo = ops::snooze_todo(&mut store, &id, until.as_deref())?;
+            match todo.frontmatter.deferred_until {
+                Some(until) => eprintln!("snoozed: {}  {} (until {until})", todo.id, todo.title()),
+                None => eprintln!("unsnoozed: {}  {}", todo.id, todo.title()),
+            }
+        }
+        Command::Due { id: Some(id), date } => {
+            let todo = ops::set_due(&mut store, &id, date.as_deref())?;
+            match todo.frontmatter.due {
+                Some(due) => eprintln!("due: {}  {} ({due})", todo.id, todo.title()),
+                None => eprintln!("due date cleared: {}  {}", todo.id, todo.title()),
+            }
+        }
+        Command::Recur { id: Some(id), rule } => {
+            let todo = ops::set_recur(&mut store, &id, rule.as_deref())?;
+            match &todo.frontmatter.recur {
+                Some(recur) => eprintln!("recur: {}  {} ({recur})", todo.id, todo.title()),
+                None => eprintln!("recurrence cleared: {}  {}", todo.id, todo.title()),
+            }
+        }
+        Command::Report { json, weeks } => report::report_summary(&store, weeks, json)?,
+        Command::ReportTime { since } => report::report_time(&store, since.as_deref())?,
+        Command::Count(filter) => {
+            let filter = filter.into_filter(&store)?;
+            println!(
+                "{}",
+                filter.apply(store.list_all(), util::now().date()).len()
+            );
+        }
+        Command::Reid(filename) => {
+            let todo = ops::reid_todo(&mut store, &filename)?;
+            eprintln!("reid: {filename} -> {}", todo.filename);
+            println!("{}", todo.id);
+        }
+        Command::Sync { push } => ops::sync(&mut store, push)?,
+        Command::MergeDriver { .. } | Command::Completions(_) => {
+            unreachable!("handled before opening the store")
+        }
+        Command::Doctor { fix } => doctor::run_doctor(&store, fix)?,
+        Command::Import {
+            from,
+            file,
+            all,
+            dry_run,
+        } => import::run_import(&mut store, from, &file, all, dry_run)?,
+        Command::Export { to, filter, output } => {
+            let filter = filter.into_filter(&store)?;
+            export::run_export(&store, to, filter, output.as_deref())?
+        }
+        Command::PlainList => {
+            let format = list_format(&store, None)?;
+            ops::list_todos(&mut store, &filter::Filter::default(), format.as_ref())?
         }
-        Command::PlainList => ops::list_todos(&mut store, false)?,
         Command::Tui => tui::run_tui(store)?,
+        Command::Edit { id: None, .. }
+        | Command::Done(None)
+        | Command::Reopen(None)
+        | Command::Delete { id: None, .. }
+        | Command::Assign { id: None, .. }
+        | Command::Unassign { id: None, .. }
+        | Command::View { id: None, .. }
+        | Command::Start(None)
+        | Command::LogTime { id: None, .. }
+        | Command::Estimate { id: None, .. }
+        | Command::Tag { id: None, .. }
+        | Command::Untag { id: None, .. }
+        | Command::Release { id: None, .. }
+        | Command::Note { id: None, .. }
+        | Command::Cancel { id: None, .. }
+        | Command::Status { id: None, .. }
+        | Command::Snooze { id: None, .. }
+        | Command::Due { id: None, .. }
+        | Command::Recur { id: None, .. } => unreachable!("picked or resolved above"),
     }
     Ok(())
 }
+
+/// The `--template` for `tdo list`, else `list_template` from the config.
+fn list_format(store: &Store, template: Option<String>) -> Result<Option<LineFormat>> {
+    template
+        .or_else(|| store.config.list_template.clone())
+        .map(|t| LineFormat::parse(&t))
+        .transpose()
+}
@@ -0,0 +1,439 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use jiff::civil::DateTime;
+
+use crate::config::Config;
+use crate::todo::{self, Comment, Event, Frontmatter, Status, WorkInterval};
+
+/// Outcome of a three-way merge of one todo file.
+pub struct Merged {
+    pub frontmatter: Frontmatter,
+    pub body: Option<String>,
+    /// Both sides changed the body differently; it now contains conflict markers.
+    pub conflict: bool,
+}
+
+/// Entry point for `tdo merge-driver %O %A %B`. Writes the merged todo to
+/// `ours` (as git expects) and returns whether the merge was clean.
+/// `config` supplies the configured statuses.
+///
+/// Files that don't parse as todos fall back to `git merge-file`, so the
+/// user still gets ordinary conflict markers.
+pub fn run_merge_driver(base: &Path, ours: &Path, theirs: &Path, config: &Config) -> Result<bool> {
+    let read = |path: &Path| {
+        fs::read_to_string(path).with_context(|| format!("failed to read: {}", path.display()))
+    };
+    let (base_raw, ours_raw, theirs_raw) = (read(base)?, read(ours)?, read(theirs)?);
+    let parsed = (
+        todo::parse_file(&base_raw, config),
+        todo::parse_file(&ours_raw, config),
+        todo::parse_file(&theirs_raw, config),
+    );
+    let (Ok(base_todo), Ok(ours_todo), Ok(theirs_todo)) = parsed else {
+        eprintln!("merge-driver: not a valid todo file, falling back to git merge-file");
+        return merge_file_fallback(base, ours, theirs);
+    };
+
+    let merged = merge(&base_todo, &ours_todo, &theirs_todo);
+    let content = todo::render_file(&merged.frontmatter, merged.body.as_deref())?;
+    fs::write(ours, content).with_context(|| format!("failed to write: {}", ours.display()))?;
+    if merged.conflict {
+        eprintln!("merge-driver: conflicting body edits in {}", ours.display());
+    }
+    Ok(!merged.conflict)
+}
+
+/// Merge three versions of a todo field by field.
+///
+/// Each field takes the side that changed it. When both sides changed a
+/// field differently: `done` wins for `status`, then any closed state over
+/// an open one, the latest `done_at` and `cancelled_at` and the earliest `assigned_at` and
+/// `created` are kept, entries added or removed on either side are applied
+/// to `assigned` and `tags`, and ours wins for the remaining fields
+/// (`title`, `estimate`, `due`, ...). Only the body can conflict; both
+/// versions are kept between conflict markers.
+pub fn merge(
+    base: &(Frontmatter, Option<String>),
+    ours: &(Frontmatter, Option<String>),
+    theirs: &(Frontmatter, Option<String>),
+) -> Merged {
+    let (b, o, t) = (&base.0, &ours.0, &theirs.0);
+
+    let status = merge3(&b.status, &o.status, &t.status, |o, t| {
+        if *t == Status::Done || (o.is_open() && !t.is_open()) {
+            t.clone()
+        } else {
+            o.clone()
+        }
+    });
+    let mut fm = Frontmatter {
+        title: merge3(&b.title, &o.title, &t.title, |o, _| o.clone()),
+        created: merge3(&b.created, &o.created, &t.created, |o, t| *o.min(t)),
+        assigned: merge_set(&b.assigned, &o.assigned, &t.assigned),
+        assigned_at: merge3(&b.assigned_at, &o.assigned_at, &t.assigned_at, |o, t| {
+            *o.max(t)
+        }),
+        done_at: merge3(&b.done_at, &o.done_at, &t.done_at, |o, t| *o.max(t)),
+        cancelled_at: merge3(&b.cancelled_at, &o.cancelled_at, &t.cancelled_at, |o, t| {
+            *o.max(t)
+        }),
+        estimate: merge3(&b.estimate, &o.estimate, &t.estimate, |o, _| *o),
+        due: merge3(&b.due, &o.due, &t.due, |o, _| *o),
+        recur: merge3(&b.recur, &o.recur, &t.recur, |o, _| o.clone()),
+        previous: merge3(&b.previous, &o.previous, &t.previous, |o, _| o.clone()),
+        tags: merge_set(&b.tags, &o.tags, &t.tags),
+        deferred_until: merge3(
+            &b.deferred_until,
+            &o.deferred_until,
+            &t.deferred_until,
+            |o, _| *o,
+        ),
+        work: merge_work(&o.work, &t.work),
+        comments: merge_comments(&o.comments, &t.comments),
+        history: merge_history(&o.history, &t.history),
+        status,
+    };
+    // Keep the timestamps consistent with the merged state.
+    if fm.status != Status::Done {
+        fm.done_at = None;
+    } else if fm.done_at.is_none() {
+        fm.done_at = o.done_at.max(t.done_at);
+    }
+    if fm.status != Status::Cancelled {
+        fm.cancelled_at = None;
+    } else if fm.cancelled_at.is_none() {
+        fm.cancelled_at = o.cancelled_at.max(t.cancelled_at);
+    }
+    if fm.assigned.is_empty() {
+        fm.assigned_at = None;
+    } else if fm.assigned_at.is_none() {
+        fm.assigned_at = o.assigned_at.or(t.assigned_at);
+    }
+
+    let (body, conflict) = merge_body(base.1.as_deref(), ours.1.as_deref(), theirs.1.as_deref());
+    Merged {
+        frontmatter: fm,
+        body,
+        conflict,
+    }
+}
+
+/// Standard three-way pick: take whichever side changed the value, and call
+/// `resolve` only when both changed it differently.
+fn merge3<T: Clone + PartialEq>(
+    base: &T,
+    ours: &T,
+    theirs: &T,
+    resolve: impl Fn(&T, &T) -> T,
+) -> T {
+    if ours == theirs || theirs == base {
+        ours.clone()
+    } else if ours == base {
+        theirs.clone()
+    } else {
+        resolve(ours, theirs)
+    }
+}
+
+/// Three-way merge of a set kept in order: an entry removed on either side
+/// is dropped, and entries added on either side are kept (ours first).
+fn merge_set(base: &[String], ours: &[String], theirs: &[String]) -> Vec<String> {
+    let removed = |s: &String| base.contains(s) && !(ours.contains(s) && theirs.contains(s));
+    let mut merged: Vec<String> = vec![];
+    for s in ours.iter().chain(theirs) {
+        if !removed(s) && !merged.contains(s) {
+            merged.push(s.clone());
+        }
+    }
+    merged
+}
+
+/// Union of both sides' work intervals, keyed by start time. If one side
+/// stopped a timer the other still shows running, the stopped one wins.
+fn merge_work(ours: &[WorkInterval], theirs: &[WorkInterval]) -> Vec<WorkInterval> {
+    let mut by_start: BTreeMap<DateTime, Option<DateTime>> = BTreeMap::new();
+    for w in ours.iter().chain(theirs) {
+        let end = by_start.entry(w.start).or_default();
+        *end = (*end).max(w.end);
+    }
+    by_start
+        .into_iter()
+        .map(|(start, end)| WorkInterval { start, end })
+        .collect()
+}
+
+/// History is append-only, so the merge is the union of both sides in time order.
+fn merge_history(ours: &[Event], theirs: &[Event]) -> Vec<Event> {
+    let mut events = ours.to_vec();
+    for event in theirs {
+        if !events.contains(event) {
+            events.push(event.clone());
+        }
+    }
+    events.sort_by_key(|e| e.at);
+    events
+}
+
+/// Comments are only ever added, so like history they are unioned in time order.
+fn merge_comments(ours: &[Comment], theirs: &[Comment]) -> Vec<Comment> {
+    let mut comments = ours.to_vec();
+    for comment in theirs {
+        if !comments.contains(comment) {
+            comments.push(comment.clone());
+        }
+    }
+    comments.sort_by_key(|c| c.at);
+    comments
+}
+
+fn merge_body(
+    base: Option<&str>,
+    ours: Option<&str>,
+    theirs: Option<&str>,
+) -> (Option<String>, bool) {
+    let norm = |b: Option<&str>| {
+        b.map(|s| s.trim_end().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    let (base, ours, theirs) = (norm(base), norm(ours), norm(theirs));
+    if ours == theirs || theirs == base {
+        (ours, false)
+    } else if ours == base {
+        (theirs, false)
+    } else {
+        let body = format!(
+            "<<<<<<< ours\n{}\n=======\n{}\n>>>>>>> theirs\n",
+            ours.unwrap_or_default(),
+            theirs.unwrap_or_default()
+        );
+        (Some(body), true)
+    }
+}
+
+/// Plain textual merge via `git merge-file`, which writes into `ours`.
+fn merge_file_fallback(base: &Path, ours: &Path, theirs: &Path) -> Result<bool> {
+    let status = Command::new("git")
+        .arg("merge-file")
+        .arg(ours)
+        .arg(base)
+        .arg(theirs)
+        .status()
+        .context("failed to run git merge-file: is git installed?")?;
+    match status.code() {
+        Some(0) => Ok(true),
+        Some(n) if n > 0 => Ok(false),
+        _ => bail!("git merge-file failed with {status}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::new_frontmatter;
+
+    fn fm(status: Status) -> Frontmatter {
+        Frontmatter {
+            status,
+            history: vec![],
+            ..new_frontmatter(
+                "fix the login bug",
+                "2026-02-20T14:30:52".parse().unwrap(),
+                None,
+            )
+        }
+    }
+
+    fn done(at: &str) -> Frontmatter {
+        Frontmatter {
+            done_at: Some(at.parse().unwrap()),
+            ..fm(Status::Done)
+        }
+    }
+
+    #[test]
+    fn one_side_done_other_side_body() {
+        let base = (fm(Status::Open), None);
+        let ours = (done("2026-02-21T10:00:00"), None);
+        let theirs = (fm(Status::Open), Some("notes\n".to_string()));
+        let merged = merge(&base, &ours, &theirs);
+        assert_eq!(merged.frontmatter.status, Status::Done);
+        assert!(merged.frontmatter.done_at.is_some());
+        assert_eq!(merged.body.as_deref(), Some("notes"));
+        assert!(!merged.conflict);
+    }
+
+    #[test]
+    fn both_done_keeps_latest_done_at() {
+        let base = (fm(Status::Open), None);
+        let ours = (done("2026-02-21T10:00:00"), None);
+        let theirs = (done("2026-02-22T10:00:00"), None);
+        let merged = merge(&base, &ours, &theirs);
+        assert_eq!(
+            merged.frontmatter.done_at,
+            Some("2026-02-22T10:00:00".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn reopen_on_one_side_clears_done_at() {
+        let base = (done("2026-02-21T10:00:00"), None);
+        let ours = (fm(Status::Open), None);
+        let theirs = (done("2026-02-21T10:00:00"), Some("more".to_string()));
+        let merged = merge(&base, &ours, &theirs);
+        assert_eq!(merged.frontmatter.status, Status::Open);
+        assert!(merged.frontmatter.done_at.is_none());
+    }
+
+    #[test]
+    fn closed_status_beats_open_status() {
+        let custom = |name: &str, open| Status::Custom {
+            name: name.to_string(),
+            open,
+        };
+        let base = (fm(Status::Open), None);
+        let ours = (fm(custom("review", true)), None);
+        let theirs = (fm(custom("wontfix", false)), None);
+        let merged = merge(&base, &ours, &theirs);
+        assert_eq!(merged.frontmatter.status, custom("wontfix", false));
+
+        let theirs = (fm(custom("blocked", true)), None);
+        let merged = merge(&base, &ours, &theirs);
+        assert_eq!(merged.frontmatter.status, custom("review", true));
+    }
+
+    #[test]
+    fn assignments_on_both_sides_are_combined() {
+        let base = (fm(Status::Open), None);
+        let mut ours = fm(Status::Open);
+        ours.assigned = vec!["alice".to_string()];
+        ours.assigned_at = Some("2026-02-21T10:00:00".parse().unwrap());
+        let mut theirs = fm(Status::Open);
+        theirs.assigned = vec!["bob".to_string()];
+        theirs.assigned_at = Some("2026-02-22T10:00:00".parse().unwrap());
+        let merged = merge(&base, &(ours, None), &(theirs, None));
+        assert_eq!(merged.frontmatter.assigned, vec!["alice", "bob"]);
+        assert_eq!(
+            merged.frontmatter.assigned_at,
+            Some("2026-02-22T10:00:00".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn unassignment_on_one_side_is_kept() {
+        let mut base = fm(Status::Open);
+        base.assigned = vec!["alice".to_string(), "bob".to_string()];
+        base.assigned_at = Some("2026-02-21T10:00:00".parse().unwrap());
+        let mut ours = base.clone();
+        ours.assigned = vec!["bob".to_string()];
+        let mut theirs = base.clone();
+        theirs.assigned.push("carol".to_string());
+        let merged = merge(&(base, None), &(ours, None), &(theirs, None));
+        assert_eq!(merged.frontmatter.assigned, vec!["bob", "carol"]);
+    }
+
+    #[test]
+    fn history_is_unioned_in_time_order() {
+        use crate::todo::EventKind;
+        let event = |at: &str, event| Event {
+            at: at.parse().unwrap(),
+            event,
+            detail: None,
+        };
+        let created = event("2026-02-20T14:30:52", EventKind::Created);
+        let base = fm(Status::Open);
+        let mut ours = fm(Status::Open);
+        ours.history = vec![
+            created.clone(),
+            event("2026-02-22T10:00:00", EventKind::Edited),
+        ];
+        let mut theirs = fm(Status::Open);
+        theirs.history = vec![
+            created.clone(),
+            event("2026-02-21T10:00:00", EventKind::Assigned),
+        ];
+        let merged = merge(&(base, None), &(ours, None), &(theirs, None));
+        let kinds: Vec<EventKind> = merged.frontmatter.history.iter().map(|e| e.event).collect();
+        assert_eq!(
+            kinds,
+            vec![EventKind::Created, EventKind::Assigned, EventKind::Edited]
+        );
+    }
+
+    #[test]
+    fn notes_from_both_sides_are_kept() {
+        let note = |at: &str, text: &str| Comment {
+            at: at.parse().unwrap(),
+            author: Some("alice".to_string()),
+            text: text.to_string(),
+        };
+        let first = note("2026-02-20T14:30:00", "first");
+        let mut base = fm(Status::Open);
+        base.comments = vec![first.clone()];
+        let mut ours = base.clone();
+        ours.comments.push(note("2026-02-22T10:00:00", "ours"));
+        let mut theirs = base.clone();
+        theirs.comments.push(note("2026-02-21T10:00:00", "theirs"));
+        let merged = merge(&(base, None), &(ours, None), &(theirs, None));
+        let texts: Vec<&str> = merged
+            .frontmatter
+            .comments
+            .iter()
+            .map(|c| c.text.as_str())
+            .collect();
+        assert_eq!(texts, ["first", "theirs", "ours"]);
+    }
+
+    #[test]
+    fn stopped_timer_wins_over_running() {
+        let start: DateTime = "2026-02-21T10:00:00".parse().unwrap();
+        let end: DateTime = "2026-02-21T11:00:00".parse().unwrap();
+        let mut base = fm(Status::Open);
+        base.work = vec![WorkInterval { start, end: None }];
+        let mut ours = base.clone();
+        ours.work[0].end = Some(end);
+        let mut theirs = base.clone();
+        theirs.work.push(WorkInterval {
+            start: end,
+            end: None,
+        });
+        let merged = merge(&(base, None), &(ours, None), &(theirs, None));
+        assert_eq!(merged.frontmatter.work.len(), 2);
+        assert_eq!(merged.frontmatter.work[0].end, Some(end));
+        assert_eq!(merged.frontmatter.work[1].end, None);
+    }
+
+    #[test]
+    fn latest_assigned_at_wins() {
+        let earlier: DateTime = "2026-02-21T10:00:00".parse().unwrap();
+        let later: DateTime = "2026-02-22T10:00:00".parse().unwrap();
+        let base = fm(Status::Open);
+        let ours = Frontmatter {
+            assigned: vec!["alice".to_string()],
+            assigned_at: Some(later),
+            ..base.clone()
+        };
+        let theirs = Frontmatter {
+            assigned: vec!["bob".to_string()],
+            assigned_at: Some(earlier),
+            ..base.clone()
+        };
+        let merged = merge(&(base, None), &(ours, None), &(theirs, None));
+        assert_eq!(merged.frontmatter.assigned_at, Some(later));
+        assert_eq!(merged.frontmatter.assigned, ["alice", "bob"]);
+    }
+
+    #[test]
+    fn conflicting_bodies_get_markers() {
+        let base = (fm(Status::Open), Some("base".to_string()));
+        let ours = (fm(Status::Open), Some("ours".to_string()));
+        let theirs = (fm(Status::Open), Some("theirs".to_string()));
+        let merged = merge(&base, &ours, &theirs);
+        assert!(merged.conflict);
+        let body = merged.body.unwrap();
+        assert!(body.contains("<<<<<<< ours\nours\n=======\ntheirs\n>>>>>>> theirs"));
+    }
+}
@@ -1,47 +1,399 @@
-use std::io::{self, BufRead, Write};
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
 
-use anyhow::{Context, Result, bail};
-use jiff::civil::DateTime;
+use anyhow::{Context, Result, anyhow, bail};
+use jiff::SignedDuration;
+use jiff::civil::{Date, DateTime};
 
+use crate::config::{Config, StaleAction, StatusColor};
+use crate::filter::Filter;
+use crate::git;
+use crate::line_format::LineFormat;
+use crate::recur::Recur;
 use crate::storage::Store;
-use crate::todo::{Frontmatter, Status, Todo};
-use crate::util::stdout_is_tty;
+use crate::template;
+use crate::todo::{Comment, Event, EventKind, Frontmatter, Status, Todo, WorkInterval};
+use crate::util::{format_duration, now, parse_date, parse_duration, use_color};
+
+/// Optional fields for `create_todo`, as given on the command line.
+#[derive(Debug, Default)]
+pub struct CreateOptions {
+    pub estimate: Option<String>,
+    pub due: Option<String>,
+    pub recur: Option<String>,
+    pub tags: Vec<String>,
+    /// Body template to use when no body is given.
+    pub template: Option<String>,
+}
 
 /// Create a new todo, returning the assigned ID.
-pub fn create_todo(store: &mut Store, title: &str, body: Option<&str>) -> Result<String> {
-    let now: DateTime = DateTime::try_from(jiff::Zoned::now())?;
-    let fm = Frontmatter {
+pub fn create_todo(
+    store: &mut Store,
+    title: &str,
+    body: Option<&str>,
+    options: &CreateOptions,
+) -> Result<String> {
+    let fm = create_frontmatter(title, options, now())?;
+    let rendered = match (body, &options.template) {
+        (None, Some(name)) => Some(render_template(store, name, title)?),
+        _ => None,
+    };
+    let id = store.create(&fm, body.or(rendered.as_deref()))?;
+    store.commit(&format!("tdo: add {id} {title}"));
+    Ok(id)
+}
+
+/// A todo to create in bulk with `create_todos`.
+#[derive(Debug, Default)]
+pub struct NewTodo {
+    pub title: String,
+    pub body: Option<String>,
+    pub options: CreateOptions,
+}
+
+/// Create several todos with one commit, returning their IDs in order.
+/// Every todo is validated first, so bad input creates nothing.
+pub fn create_todos(store: &mut Store, todos: &[NewTodo]) -> Result<Vec<String>> {
+    let now = now();
+    let mut frontmatters = vec![];
+    for todo in todos {
+        let fm = create_frontmatter(&todo.title, &todo.options, now)
+            .with_context(|| format!("invalid todo '{}'", todo.title))?;
+        frontmatters.push(fm);
+    }
+    let mut ids = vec![];
+    for (todo, fm) in todos.iter().zip(&frontmatters) {
+        ids.push(store.create(fm, todo.body.as_deref())?);
+    }
+    if !ids.is_empty() {
+        store.commit(&format!("tdo: add {} todo(s)", ids.len()));
+    }
+    Ok(ids)
+}
+
+fn create_frontmatter(title: &str, options: &CreateOptions, now: DateTime) -> Result<Frontmatter> {
+    if title.trim().is_empty() {
+        bail!("title can't be empty");
+    }
+    Ok(Frontmatter {
+        estimate: options
+            .estimate
+            .as_deref()
+            .map(parse_estimate)
+            .transpose()?,
+        due: options.due.as_deref().map(parse_due).transpose()?,
+        recur: options.recur.as_deref().map(str::parse).transpose()?,
+        tags: parse_tags(&options.tags)?,
+        ..new_frontmatter(title, now, None)
+    })
+}
+
+/// The body of template `name` from `.todo/templates/` for a todo titled
+/// `title`.
+pub fn render_template(store: &Store, name: &str, title: &str) -> Result<String> {
+    let vars = template::Vars {
+        title,
+        date: now().date().to_string(),
+        user: store.current_user().unwrap_or_default(),
+    };
+    template::render_named(store.dir(), name, &vars)
+}
+
+/// Body text from `--body TEXT`, `--body -` (stdin) or `--body-file PATH`
+/// (`-` is stdin too).
+pub fn read_body(body: Option<String>, body_file: Option<&Path>) -> Result<Option<String>> {
+    match (body, body_file) {
+        (Some(body), _) if body == "-" => read_stdin().map(Some),
+        (Some(body), _) => Ok(Some(body)),
+        (None, Some(path)) if path == Path::new("-") => read_stdin().map(Some),
+        (None, Some(path)) => fs::read_to_string(path)
+            .map(Some)
+            .with_context(|| format!("failed to read: {}", path.display())),
+        (None, None) => Ok(None),
+    }
+}
+
+fn read_stdin() -> Result<String> {
+    io::read_to_string(io::stdin()).context("failed to read stdin")
+}
+
+/// Todos for `tdo add --from-stdin`: one per non-empty line, with list
+/// markers (`- `, `* `, `- [ ] `) stripped, or, when the input starts with
+/// `---`, one per Markdown document with a frontmatter block. `defaults`
+/// apply to each; frontmatter values take precedence and tags add up.
+pub fn parse_stdin_todos(raw: &str, defaults: &CreateOptions) -> Result<Vec<NewTodo>> {
+    let with_defaults = |title: &str, body: Option<String>| NewTodo {
+        title: title.to_string(),
+        body,
+        options: CreateOptions {
+            estimate: defaults.estimate.clone(),
+            due: defaults.due.clone(),
+            recur: defaults.recur.clone(),
+            tags: defaults.tags.clone(),
+            template: None,
+        },
+    };
+    if !raw.trim_start().starts_with("---") {
+        return Ok(raw
+            .lines()
+            .map(|line| {
+                let line = line.trim();
+                ["- [ ] ", "- ", "* "]
+                    .iter()
+                    .find_map(|marker| line.strip_prefix(marker))
+                    .unwrap_or(line)
+                    .trim()
+            })
+            .filter(|title| !title.is_empty())
+            .map(|title| with_defaults(title, None))
+            .collect());
+    }
+
+    let mut todos = vec![];
+    for (i, (yaml, body)) in split_documents(raw).into_iter().enumerate() {
+        let doc: StdinDocument = serde_yml::from_str(&yaml)
+            .with_context(|| format!("invalid frontmatter in document {}", i + 1))?;
+        let body = Some(body.trim_start_matches('\n').to_string()).filter(|b| !b.trim().is_empty());
+        let mut todo = with_defaults(&doc.title, body);
+        todo.options.estimate = doc.estimate.or(todo.options.estimate);
+        todo.options.due = doc.due.or(todo.options.due);
+        todo.options.recur = doc.recur.or(todo.options.recur);
+        todo.options.tags.extend(doc.tags);
+        todos.push(todo);
+    }
+    Ok(todos)
+}
+
+/// Frontmatter keys read from stdin documents; others (e.g. from an
+/// existing todo file) are ignored.
+#[derive(serde::Deserialize)]
+struct StdinDocument {
+    title: String,
+    #[serde(default)]
+    estimate: Option<String>,
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    recur: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Split concatenated Markdown files into (frontmatter, body) pairs. A `---`
+/// line in a body starts the next document when the line after it looks
+/// like a frontmatter key, so horizontal rules in bodies survive.
+fn split_documents(raw: &str) -> Vec<(String, String)> {
+    let lines: Vec<&str> = raw.trim_start().lines().collect();
+    let looks_like_key = |line: Option<&&str>| {
+        line.and_then(|l| l.split_once(':'))
+            .is_some_and(|(key, _)| {
+                !key.is_empty() && key.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+            })
+    };
+    let mut docs: Vec<(String, String)> = vec![];
+    let mut in_frontmatter = false;
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim_end() == "---" {
+            if in_frontmatter {
+                in_frontmatter = false;
+                continue;
+            }
+            if docs.is_empty() || looks_like_key(lines.get(i + 1)) {
+                docs.push((String::new(), String::new()));
+                in_frontmatter = true;
+                continue;
+            }
+        }
+        if let Some((yaml, body)) = docs.last_mut() {
+            let target = if in_frontmatter { yaml } else { body };
+            target.push_str(line);
+            target.push('\n');
+        }
+    }
+    docs
+}
+
+/// Frontmatter for a fresh open todo, with its `created` history event.
+pub fn new_frontmatter(title: &str, now: DateTime, detail: Option<String>) -> Frontmatter {
+    Frontmatter {
         title: title.to_string(),
         created: now,
         status: Status::Open,
-        assigned: None,
+        assigned: vec![],
         assigned_at: None,
         done_at: None,
-    };
-    store.create(&fm, body)
+        cancelled_at: None,
+        estimate: None,
+        due: None,
+        recur: None,
+        previous: None,
+        tags: vec![],
+        deferred_until: None,
+        work: vec![],
+        comments: vec![],
+        history: vec![Event {
+            at: now,
+            event: EventKind::Created,
+            detail,
+        }],
+    }
+}
+
+/// Turn an ID prefix or title query (see `Store::find_by_query`) into a todo
+/// ID. Several matches are an error listing them, or with `interactive` a
+/// prompt to pick one.
+pub fn resolve_id(store: &Store, query: &str, interactive: bool) -> Result<String> {
+    let matches = store.find_by_query(query)?;
+    if let [todo] = matches.as_slice() {
+        return Ok(todo.id.clone());
+    }
+    let list: Vec<String> = matches
+        .iter()
+        .map(|t| format!("  {}  {}", t.id, t.title()))
+        .collect();
+    if !interactive {
+        bail!(
+            "ambiguous query '{query}' matches {} todos:\n{}",
+            matches.len(),
+            list.join("\n")
+        );
+    }
+    for (i, line) in list.iter().enumerate() {
+        eprintln!("{:>2}){line}", i + 1);
+    }
+    eprint!("Which one? [1-{}] ", matches.len());
+    io::stderr().flush()?;
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    match line.trim().parse::<usize>() {
+        Ok(n) if (1..=matches.len()).contains(&n) => Ok(matches[n - 1].id.clone()),
+        _ => bail!("no todo chosen"),
+    }
 }
 
-/// Mark a todo as done. Returns the updated todo.
-pub fn mark_done(store: &mut Store, id: &str) -> Result<Todo> {
+/// Mark a todo as done. Returns the updated todo and, for a recurring todo,
+/// the next occurrence created in its place.
+pub fn mark_done(store: &mut Store, id: &str) -> Result<(Todo, Option<Todo>)> {
     let mut todo = store.find_by_id(id)?;
+    stop_running(&mut todo);
+    let mut next = None;
+    if todo.frontmatter.status != Status::Done {
+        if let Some(recur) = &todo.frontmatter.recur
+            && !has_next_occurrence(store, &todo.id)
+        {
+            next = Some(create_next_occurrence(store, &todo, recur)?);
+        }
+        let detail = next.as_ref().map(|n| format!("next {}", n.id));
+        todo.record(EventKind::Done, detail.as_deref());
+    }
     todo.frontmatter.status = Status::Done;
+    todo.frontmatter.cancelled_at = None;
     if todo.frontmatter.done_at.is_none() {
-        let now: DateTime = DateTime::try_from(jiff::Zoned::now())?;
-        todo.frontmatter.done_at = Some(now);
+        todo.frontmatter.done_at = Some(now());
     }
     store.save(&todo)?;
+    commit_todo(store, "done", &todo);
+    Ok((todo, next))
+}
+
+/// Whether a later occurrence of this todo already exists, e.g. because it
+/// was completed, reopened and completed again.
+fn has_next_occurrence(store: &Store, id: &str) -> bool {
+    store
+        .list_all()
+        .iter()
+        .any(|t| t.frontmatter.previous.as_deref() == Some(id))
+}
+
+/// Create the occurrence following `done`, due on the first date of the rule
+/// after its due date (or today) that is still in the future.
+fn create_next_occurrence(store: &mut Store, done: &Todo, recur: &Recur) -> Result<Todo> {
+    let now = now();
+    let today = now.date();
+    let from = done.frontmatter.due.unwrap_or(today);
+    let recur = &recur.anchored_at(from);
+    let mut due = recur.next_after(from);
+    while due <= today {
+        due = recur.next_after(due);
+    }
+    let fm = Frontmatter {
+        assigned: done.frontmatter.assigned.clone(),
+        assigned_at: done.frontmatter.assigned_at.map(|_| now),
+        estimate: done.frontmatter.estimate,
+        due: Some(due),
+        recur: Some(recur.clone()),
+        previous: Some(done.id.clone()),
+        tags: done.frontmatter.tags.clone(),
+        ..new_frontmatter(done.title(), now, Some(format!("after {}", done.id)))
+    };
+    let id = store.create(&fm, done.body.as_deref())?;
+    store.find_by_id(&id)
+}
+
+/// Cancel a todo: close it without marking it done, keeping the record.
+/// `reason` is recorded in its history. Returns the updated todo.
+pub fn cancel_todo(store: &mut Store, id: &str, reason: Option<&str>) -> Result<Todo> {
+    let mut todo = store.find_by_id(id)?;
+    stop_running(&mut todo);
+    if todo.frontmatter.status != Status::Cancelled {
+        todo.record(EventKind::Cancelled, reason);
+    }
+    todo.frontmatter.status = Status::Cancelled;
+    todo.frontmatter.done_at = None;
+    if todo.frontmatter.cancelled_at.is_none() {
+        todo.frontmatter.cancelled_at = Some(now());
+    }
+    store.save(&todo)?;
+    commit_todo(store, "cancel", &todo);
     Ok(todo)
 }
 
-/// Reopen a done todo. Returns the updated todo.
+/// Reopen a done or cancelled todo. Returns the updated todo.
 pub fn reopen_todo(store: &mut Store, id: &str) -> Result<Todo> {
     let mut todo = store.find_by_id(id)?;
+    if !todo.is_open() {
+        todo.record(EventKind::Reopened, None);
+    }
     todo.frontmatter.status = Status::Open;
     todo.frontmatter.done_at = None;
+    todo.frontmatter.cancelled_at = None;
     store.save(&todo)?;
+    commit_todo(store, "reopen", &todo);
     Ok(todo)
 }
 
+/// Move a todo to the workflow state `name`: a built-in or configured
+/// status. Moving to `done` is `mark_done`, including the next occurrence of
+/// a recurring todo, which is returned alongside; `cancelled` is
+/// `cancel_todo`.
+pub fn set_status(store: &mut Store, id: &str, name: &str) -> Result<(Todo, Option<Todo>)> {
+    let status = store.config.status(name).ok_or_else(|| {
+        anyhow!(
+            "unknown status '{name}' (expected {})",
+            store.config.status_names().join(", ")
+        )
+    })?;
+    match status {
+        Status::Done => return mark_done(store, id),
+        Status::Cancelled => return Ok((cancel_todo(store, id, None)?, None)),
+        _ => {}
+    }
+    let mut todo = store.find_by_id(id)?;
+    if status == Status::Open && !todo.is_open() {
+        return Ok((reopen_todo(store, id)?, None));
+    }
+    if todo.frontmatter.status != status {
+        todo.record(EventKind::Status, Some(status.name()));
+        todo.frontmatter.status = status;
+    }
+    todo.frontmatter.done_at = None;
+    todo.frontmatter.cancelled_at = None;
+    store.save(&todo)?;
+    commit_todo(store, "status", &todo);
+    Ok((todo, None))
+}
+
 /// Delete a todo. Returns Some(deleted_todo) on success, None if cancelled.
 ///
 /// - Interactive mode: prompts for confirmation
@@ -65,6 +417,7 @@ pub fn delete_todo(
         bail!("use --force to delete non-interactively");
     }
     let deleted = store.delete(&todo.id)?;
+    commit_todo(store, "delete", &deleted);
     Ok(Some(deleted))
 }
 
@@ -81,12 +434,15 @@ pub fn edit_todo(
 ) -> Result<()> {
     if let Some(b) = new_body {
         let mut todo = store.find_by_id(id)?;
-        todo.body = if b.is_empty() {
+        todo.body = if b.trim().is_empty() {
             None
         } else {
             Some(b.to_string())
         };
-        store.save(&todo)
+        todo.record(EventKind::Edited, None);
+        store.save(&todo)?;
+        commit_todo(store, "edit", &todo);
+        Ok(())
     } else if interactive {
         let todo = store.find_by_id(id)?;
         let path = store.path_for(&todo);
@@ -101,37 +457,513 @@ pub fn edit_todo(
             bail!("editor exited with status {status}");
         }
         store.refresh(&todo.id)?;
+        let mut edited = store.find_by_id(&todo.id)?;
+        if edited.frontmatter != todo.frontmatter || edited.body != todo.body {
+            edited.record(EventKind::Edited, None);
+            store.save(&edited)?;
+        }
+        commit_todo(store, "edit", &edited);
         Ok(())
     } else {
         bail!("cannot open editor non-interactively; use --body");
     }
 }
 
-/// Assign a todo. Returns the updated todo.
-pub fn assign_todo(store: &mut Store, id: &str, name: Option<&str>) -> Result<Todo> {
+/// Add assignees to a todo; with no names, assign the current user (or
+/// make an anonymous assignment if there is none). Returns the updated todo.
+pub fn assign_todo(store: &mut Store, id: &str, names: &[String]) -> Result<Todo> {
     let mut todo = store.find_by_id(id)?;
-    todo.frontmatter.assigned = Some(name.unwrap_or("").to_string());
+    let names = match names {
+        [] => vec![store.current_user().unwrap_or_default()],
+        names => names.to_vec(),
+    };
+    for name in names {
+        if todo.is_assigned_to(&name) || (name.is_empty() && todo.is_assigned()) {
+            continue;
+        }
+        // A named assignee replaces an anonymous assignment.
+        todo.frontmatter.assigned.retain(|a| !a.is_empty());
+        todo.record(EventKind::Assigned, Some(&name));
+        todo.frontmatter.assigned.push(name);
+    }
     if todo.frontmatter.assigned_at.is_none() {
-        let now: DateTime = DateTime::try_from(jiff::Zoned::now())?;
-        todo.frontmatter.assigned_at = Some(now);
+        todo.frontmatter.assigned_at = Some(now());
+    }
+    store.save(&todo)?;
+    commit_todo(store, "assign", &todo);
+    Ok(todo)
+}
+
+/// Remove the given assignees from a todo, or all of them if `names` is
+/// empty. Returns the updated todo.
+pub fn unassign_todo(store: &mut Store, id: &str, names: &[String]) -> Result<Todo> {
+    let mut todo = store.find_by_id(id)?;
+    if names.is_empty() {
+        if todo.is_assigned() {
+            todo.record(EventKind::Unassigned, None);
+        }
+        todo.frontmatter.assigned.clear();
+    } else {
+        for name in names {
+            if !todo.is_assigned_to(name) {
+                bail!("{} is not assigned to {name}", todo.id);
+            }
+            todo.frontmatter.assigned.retain(|a| a != name);
+            todo.record(EventKind::Unassigned, Some(name));
+        }
+    }
+    if !todo.is_assigned() {
+        todo.frontmatter.assigned_at = None;
+    }
+    store.save(&todo)?;
+    commit_todo(store, "unassign", &todo);
+    Ok(todo)
+}
+
+/// Exit status of `tdo claim` when no todo is available, so scripts can tell
+/// "no work" apart from errors (1) and usage errors (2).
+pub const EXIT_NOTHING_TO_CLAIM: i32 = 3;
+
+/// Atomically pick the oldest open, unsnoozed, unassigned todo carrying all
+/// of `tags` and assign it to `name` (default: the current user). Returns
+/// `None` if there is nothing to claim. The store lock makes concurrent
+/// claims from several processes safe.
+pub fn claim_next(store: &mut Store, tags: &[String], name: Option<&str>) -> Result<Option<Todo>> {
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => current_user(store)?,
+    };
+    let tags = parse_tags(tags)?;
+    // Stale assignments released by the policy are up for grabs again.
+    enforce_stale_policy(store)?;
+    let Some(id) = store
+        .list_open()
+        .into_iter()
+        .find(|t| {
+            t.frontmatter.status == Status::Open
+                && !t.is_assigned()
+                && tags.iter().all(|tag| t.has_tag(tag))
+        })
+        .map(|t| t.id.clone())
+    else {
+        return Ok(None);
+    };
+    let mut todo = store.find_by_id(&id)?;
+    todo.frontmatter.assigned = vec![name.clone()];
+    todo.frontmatter.assigned_at = Some(now());
+    todo.record(EventKind::Assigned, Some(&name));
+    store.save(&todo)?;
+    commit_todo(store, "claim", &todo);
+    Ok(Some(todo))
+}
+
+/// Give back a claimed todo: remove `name` (default: the current user) from
+/// its assignees. Returns the updated todo.
+pub fn release_todo(store: &mut Store, id: &str, name: Option<&str>) -> Result<Todo> {
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => current_user(store)?,
+    };
+    let todo = store.find_by_id(id)?;
+    if !todo.is_assigned_to(&name) {
+        bail!("{} is not claimed by {name}", todo.id);
+    }
+    unassign_todo(store, id, &[name])
+}
+
+/// Append a timestamped note to a todo, attributed to `author` or the
+/// current user if there is one. Returns the updated todo.
+pub fn add_comment(store: &mut Store, id: &str, text: &str, author: Option<&str>) -> Result<Todo> {
+    let text = text.trim();
+    if text.is_empty() {
+        bail!("note can't be empty");
+    }
+    let mut todo = store.find_by_id(id)?;
+    let author = match author {
+        Some(name) => Some(name.to_string()),
+        None => store.current_user(),
+    };
+    todo.frontmatter.comments.push(Comment {
+        at: now(),
+        author,
+        text: text.to_string(),
+    });
+    todo.record(EventKind::Noted, text.lines().next());
+    store.save(&todo)?;
+    commit_todo(store, "note", &todo);
+    Ok(todo)
+}
+
+/// Tag added to stale assignments under `stale_action: tag`.
+pub const STALE_TAG: &str = "stale";
+
+/// Open todos assigned for longer than the configured `stale_after`,
+/// longest-assigned first.
+pub fn stale_todos(store: &Store, now: DateTime) -> Vec<&Todo> {
+    let after = store.config.stale_after;
+    let mut stale: Vec<&Todo> = store
+        .list_all()
+        .iter()
+        .filter(|t| t.is_stale(now, after))
+        .collect();
+    stale.sort_by_key(|t| t.frontmatter.assigned_at);
+    stale
+}
+
+/// Apply the configured `stale_action` to stale assignments, recording a
+/// `stale` event in each todo's history. Returns the todos it changed.
+pub fn enforce_stale_policy(store: &mut Store) -> Result<Vec<Todo>> {
+    let action = store.config.stale_action;
+    if action == StaleAction::Warn {
+        return Ok(vec![]);
+    }
+    let ids: Vec<String> = stale_todos(store, now())
+        .into_iter()
+        .filter(|t| action != StaleAction::Tag || !t.has_tag(STALE_TAG))
+        .map(|t| t.id.clone())
+        .collect();
+    let mut changed = Vec::new();
+    for id in &ids {
+        let mut todo = store.find_by_id(id)?;
+        let detail = match action {
+            StaleAction::Unassign => {
+                let names = todo.assignees();
+                todo.frontmatter.assigned.clear();
+                todo.frontmatter.assigned_at = None;
+                format!("unassigned {names}").trim_end().to_string()
+            }
+            StaleAction::Tag => {
+                todo.frontmatter.tags.push(STALE_TAG.to_string());
+                format!("tagged {STALE_TAG}")
+            }
+            StaleAction::Warn => unreachable!("warn changes nothing"),
+        };
+        todo.record(EventKind::Stale, Some(&detail));
+        store.save(&todo)?;
+        changed.push(todo);
+    }
+    if !changed.is_empty() {
+        store.commit(&format!(
+            "tdo: {} {} stale todo{}",
+            if action == StaleAction::Unassign {
+                "unassign"
+            } else {
+                "tag"
+            },
+            changed.len(),
+            if changed.len() == 1 { "" } else { "s" }
+        ));
+    }
+    Ok(changed)
+}
+
+/// Print stale assignments, after applying the configured policy to them.
+pub fn list_stale(store: &mut Store) -> Result<()> {
+    for todo in enforce_stale_policy(store)? {
+        report_enforced(&todo);
+    }
+    let now = now();
+    let color = use_color();
+    let mut out = io::stdout().lock();
+    for todo in stale_todos(store, now) {
+        let days = days_assigned(todo, now);
+        let id = if color {
+            format!("{CYAN}{}{RESET}", todo.id)
+        } else {
+            todo.id.clone()
+        };
+        writeln!(
+            out,
+            "{id}  {}{} ({days} days ago)",
+            todo.title(),
+            todo.assigned_suffix()
+        )?;
+    }
+    Ok(())
+}
+
+/// Whole days since `todo` was assigned.
+fn days_assigned(todo: &Todo, now: DateTime) -> i64 {
+    todo.frontmatter
+        .assigned_at
+        .map_or(0, |at| now.duration_since(at).as_hours() / 24)
+}
+
+/// Tell the user (on stderr) what the stale policy did to `todo`.
+fn report_enforced(todo: &Todo) {
+    let detail = todo
+        .history()
+        .last()
+        .and_then(|e| e.detail.clone())
+        .unwrap_or_default();
+    eprintln!("⚠ stale: {detail}: {}  {}", todo.id, todo.title());
+}
+
+/// Add tags to a todo. Returns the updated todo.
+pub fn tag_todo(store: &mut Store, id: &str, tags: &[String]) -> Result<Todo> {
+    let mut todo = store.find_by_id(id)?;
+    let before = todo.frontmatter.tags.clone();
+    for tag in parse_tags(tags)? {
+        if !todo.has_tag(&tag) {
+            todo.frontmatter.tags.push(tag);
+        }
+    }
+    if todo.frontmatter.tags != before {
+        let detail = format!("tags {}", todo.frontmatter.tags.join(", "));
+        todo.record(EventKind::Edited, Some(&detail));
+        store.save(&todo)?;
+        commit_todo(store, "tag", &todo);
+    }
+    Ok(todo)
+}
+
+/// Remove tags from a todo. Returns the updated todo.
+pub fn untag_todo(store: &mut Store, id: &str, tags: &[String]) -> Result<Todo> {
+    let mut todo = store.find_by_id(id)?;
+    let tags = parse_tags(tags)?;
+    let before = todo.frontmatter.tags.len();
+    todo.frontmatter.tags.retain(|t| !tags.contains(t));
+    if todo.frontmatter.tags.len() != before {
+        let detail = format!("untagged {}", tags.join(", "));
+        todo.record(EventKind::Edited, Some(&detail));
+        store.save(&todo)?;
+        commit_todo(store, "untag", &todo);
+    }
+    Ok(todo)
+}
+
+/// Normalise tags as given on the command line: a leading `#` is dropped and
+/// tags may not be empty or contain whitespace or commas.
+pub fn parse_tags(tags: &[String]) -> Result<Vec<String>> {
+    let mut parsed: Vec<String> = vec![];
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#');
+        if tag.is_empty() || tag.contains(|c: char| c.is_whitespace() || c == ',') {
+            bail!("invalid tag '{tag}': tags can't be empty or contain spaces or commas");
+        }
+        if !parsed.iter().any(|t| t == tag) {
+            parsed.push(tag.to_string());
+        }
+    }
+    Ok(parsed)
+}
+
+/// Start a work timer on a todo. Only one timer runs at a time, so a timer
+/// running on another todo is stopped first and returned alongside.
+pub fn start_timer(store: &mut Store, id: &str) -> Result<(Todo, Option<Todo>)> {
+    let mut todo = store.find_by_id(id)?;
+    if todo.running_since().is_some() {
+        bail!("timer already running on {}", todo.id);
     }
+    let stopped = match running_todo(store) {
+        Some(running) => Some(stop_timer(store, Some(&running.id))?),
+        None => None,
+    };
+    todo.frontmatter.work.push(WorkInterval {
+        start: now(),
+        end: None,
+    });
+    todo.record(EventKind::Started, None);
     store.save(&todo)?;
+    commit_todo(store, "start", &todo);
+    Ok((todo, stopped))
+}
+
+/// Stop the timer on a todo, or whichever timer is running if no ID is given.
+pub fn stop_timer(store: &mut Store, id: Option<&str>) -> Result<Todo> {
+    let mut todo = match id {
+        Some(id) => store.find_by_id(id)?,
+        None => running_todo(store).ok_or_else(|| anyhow!("no timer is running"))?,
+    };
+    if !stop_running(&mut todo) {
+        bail!("no timer running on {}", todo.id);
+    }
+    store.save(&todo)?;
+    commit_todo(store, "stop", &todo);
     Ok(todo)
 }
 
-/// Unassign a todo. Returns the updated todo.
-pub fn unassign_todo(store: &mut Store, id: &str) -> Result<Todo> {
+/// Record time spent on a todo without a timer, as an interval ending now.
+pub fn log_time(store: &mut Store, id: &str, duration: &str) -> Result<Todo> {
+    let spent = parse_duration(duration)?;
+    if spent.is_negative() || spent.is_zero() {
+        bail!("duration must be positive");
+    }
     let mut todo = store.find_by_id(id)?;
-    todo.frontmatter.assigned = None;
-    todo.frontmatter.assigned_at = None;
+    let end = now();
+    todo.frontmatter.work.push(WorkInterval {
+        start: end.saturating_sub(spent),
+        end: Some(end),
+    });
+    todo.frontmatter
+        .work
+        .sort_by_key(|w| (w.end.is_none(), w.start));
+    todo.record(EventKind::Logged, Some(&format_duration(spent)));
     store.save(&todo)?;
+    commit_todo(store, "log time", &todo);
     Ok(todo)
 }
 
-/// View a todo's full content.
-pub fn view_todo(store: &Store, id: &str) -> Result<()> {
+/// Set or clear (`None`) a todo's estimate. Returns the updated todo.
+pub fn set_estimate(store: &mut Store, id: &str, estimate: Option<&str>) -> Result<Todo> {
+    let mut todo = store.find_by_id(id)?;
+    let estimate = estimate.map(parse_estimate).transpose()?;
+    if todo.frontmatter.estimate != estimate {
+        let detail = match estimate {
+            Some(e) => format!("estimate {}", format_duration(e)),
+            None => "estimate cleared".to_string(),
+        };
+        todo.frontmatter.estimate = estimate;
+        todo.record(EventKind::Edited, Some(&detail));
+        store.save(&todo)?;
+        commit_todo(store, "estimate", &todo);
+    }
+    Ok(todo)
+}
+
+/// Snooze an open todo until a date, or unsnooze it with `None`. Snoozed
+/// todos are left out of `Store::list_open`. Returns the updated todo.
+pub fn snooze_todo(store: &mut Store, id: &str, until: Option<&str>) -> Result<Todo> {
+    let mut todo = store.find_by_id(id)?;
+    let today = now().date();
+    let until = until.map(|s| parse_date(s, today)).transpose()?;
+    match until {
+        Some(until) => {
+            if !todo.is_open() {
+                bail!(
+                    "{} is {}; only open todos can be snoozed",
+                    todo.id,
+                    todo.frontmatter.status
+                );
+            }
+            if until <= today {
+                bail!("snooze date {until} is not in the future");
+            }
+            todo.frontmatter.deferred_until = Some(until);
+            todo.record(EventKind::Snoozed, Some(&until.to_string()));
+        }
+        None => {
+            if todo.frontmatter.deferred_until.take().is_none() {
+                return Ok(todo);
+            }
+            todo.record(EventKind::Edited, Some("snooze cleared"));
+        }
+    }
+    store.save(&todo)?;
+    commit_todo(store, "snooze", &todo);
+    Ok(todo)
+}
+
+/// Set or clear (`None`) a todo's due date. Returns the updated todo.
+pub fn set_due(store: &mut Store, id: &str, due: Option<&str>) -> Result<Todo> {
+    let mut todo = store.find_by_id(id)?;
+    let due = due.map(parse_due).transpose()?;
+    if todo.frontmatter.due != due {
+        let detail = match due {
+            Some(d) => format!("due {d}"),
+            None => "due date cleared".to_string(),
+        };
+        todo.frontmatter.due = due;
+        todo.record(EventKind::Edited, Some(&detail));
+        store.save(&todo)?;
+        commit_todo(store, "due", &todo);
+    }
+    Ok(todo)
+}
+
+/// Set or clear (`None`) a todo's recurrence rule. Returns the updated todo.
+pub fn set_recur(store: &mut Store, id: &str, rule: Option<&str>) -> Result<Todo> {
+    let mut todo = store.find_by_id(id)?;
+    let recur: Option<Recur> = rule.map(str::parse).transpose()?;
+    if todo.frontmatter.recur != recur {
+        let detail = match &recur {
+            Some(r) => format!("recur {r}"),
+            None => "recurrence cleared".to_string(),
+        };
+        todo.frontmatter.recur = recur;
+        todo.record(EventKind::Edited, Some(&detail));
+        store.save(&todo)?;
+        commit_todo(store, "recur", &todo);
+    }
+    Ok(todo)
+}
+
+fn parse_estimate(s: &str) -> Result<SignedDuration> {
+    let estimate = parse_duration(s)?;
+    if estimate.is_negative() || estimate.is_zero() {
+        bail!("estimate must be positive");
+    }
+    Ok(estimate)
+}
+
+fn parse_due(s: &str) -> Result<Date> {
+    parse_date(s, now().date())
+}
+
+/// The todo with a running timer, if any.
+pub fn running_todo(store: &Store) -> Option<Todo> {
+    store
+        .list_all()
+        .iter()
+        .find(|t| t.running_since().is_some())
+        .cloned()
+}
+
+/// End the running work interval, if any. Returns whether one was running.
+fn stop_running(todo: &mut Todo) -> bool {
+    if todo.running_since().is_none() {
+        return false;
+    }
+    if let Some(last) = todo.frontmatter.work.last_mut() {
+        last.end = Some(now());
+    }
+    let spent = todo.frontmatter.work.last().map(|w| w.duration(now()));
+    todo.record(EventKind::Stopped, spent.map(format_duration).as_deref());
+    true
+}
+
+/// Give a todo a fresh ID. Accepts a filename or a path to one inside the
+/// store, since the old ID is ambiguous by definition.
+pub fn reid_todo(store: &mut Store, file: &str) -> Result<Todo> {
+    let filename = std::path::Path::new(file)
+        .file_name()
+        .and_then(|f| f.to_str())
+        .ok_or_else(|| anyhow!("invalid filename '{file}'"))?;
+    let todo = store.reid(filename)?;
+    store.commit(&format!(
+        "tdo: reid {filename} -> {} {}",
+        todo.id,
+        todo.title()
+    ));
+    Ok(todo)
+}
+
+/// Pull the git repository containing the store (rebasing local commits)
+/// and reload every todo, optionally pushing afterwards.
+pub fn sync(store: &mut Store, push: bool) -> Result<()> {
+    git::pull_rebase(store.dir())?;
+    store.reload()?;
+    for files in &store.duplicates {
+        eprintln!(
+            "warning: duplicate id in {}; run `tdo reid <filename>` or `tdo doctor --fix`",
+            files.join(", ")
+        );
+    }
+    if push {
+        git::push(store.dir())?;
+    }
+    eprintln!(
+        "synced: {} open, {} total",
+        store.list_open_with_snoozed().len(),
+        store.list_all().len()
+    );
+    Ok(())
+}
+
+/// View a todo's full content, optionally followed by its activity history.
+pub fn view_todo(store: &Store, id: &str, history: bool) -> Result<()> {
     let todo = store.find_by_id(id)?;
-    let color = stdout_is_tty();
+    let color = use_color();
 
     let mut out = io::stdout().lock();
     if color {
@@ -139,124 +971,250 @@ pub fn view_todo(store: &Store, id: &str) -> Result<()> {
     } else {
         writeln!(out, "{}  {}", todo.id, todo.title())?;
     }
-    writeln!(
-        out,
-        "status:   {}",
-        if todo.is_open() { "open" } else { "done" }
-    )?;
+    writeln!(out, "status:   {}", todo.frontmatter.status)?;
     writeln!(out, "created:  {}", todo.frontmatter.created)?;
-    if let Some(ref assigned) = todo.frontmatter.assigned {
-        if assigned.is_empty() {
-            writeln!(out, "assigned: (unspecified)")?;
-        } else {
-            writeln!(out, "assigned: {assigned}")?;
+    if todo.is_assigned() {
+        match todo.assignees() {
+            names if names.is_empty() => writeln!(out, "assigned: (unspecified)")?,
+            names => writeln!(out, "assigned: {names}")?,
         }
     }
     if let Some(ref done_at) = todo.frontmatter.done_at {
         writeln!(out, "done_at:  {done_at}")?;
     }
+    if let Some(ref cancelled_at) = todo.frontmatter.cancelled_at {
+        writeln!(out, "closed:   {cancelled_at}")?;
+    }
+    if let Some(estimate) = todo.frontmatter.estimate {
+        writeln!(out, "estimate: {}", format_duration(estimate))?;
+    }
+    if let Some(due) = todo.frontmatter.due {
+        writeln!(out, "due:      {due}")?;
+    }
+    if let Some(ref recur) = todo.frontmatter.recur {
+        writeln!(out, "recur:    {recur}")?;
+    }
+    if let Some(ref previous) = todo.frontmatter.previous {
+        writeln!(out, "previous: {previous}")?;
+    }
+    if !todo.frontmatter.tags.is_empty() {
+        writeln!(out, "tags:     {}", todo.frontmatter.tags.join(", "))?;
+    }
+    if let Some(until) = todo.frontmatter.deferred_until
+        && todo.is_snoozed(now().date())
+    {
+        writeln!(out, "snoozed:  until {until}")?;
+    }
+    if !todo.frontmatter.work.is_empty() {
+        let spent = format_duration(todo.time_spent(now()));
+        match todo.running_since() {
+            Some(since) => writeln!(
+                out,
+                "time:     {spent} (running since {})",
+                since.strftime("%H:%M")
+            )?,
+            None => writeln!(out, "time:     {spent}")?,
+        }
+    }
     if let Some(ref body) = todo.body {
         writeln!(out)?;
-        writeln!(out, "{body}")?;
+        writeln!(out, "{}", body.trim_end())?;
+    }
+    if !todo.frontmatter.comments.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "notes:")?;
+        for comment in &todo.frontmatter.comments {
+            writeln!(out, "  {}", comment.to_string().replace('\n', "\n  "))?;
+        }
+    }
+    if history {
+        writeln!(out)?;
+        writeln!(out, "history:")?;
+        for event in todo.history() {
+            writeln!(out, "  {event}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Print the activity history of every todo in the store, oldest first.
+/// With `limit`, only the most recent `limit` events are shown.
+pub fn log_todos(store: &Store, limit: Option<usize>) -> Result<()> {
+    let mut events: Vec<(Event, &Todo)> = store
+        .list_all()
+        .iter()
+        .flat_map(|t| t.history().into_iter().map(move |e| (e, t)))
+        .collect();
+    events.sort_by_key(|(e, _)| e.at);
+    let skip = limit.map_or(0, |n| events.len().saturating_sub(n));
+
+    let color = use_color();
+    let mut out = io::stdout().lock();
+    for (event, todo) in events.iter().skip(skip) {
+        if color {
+            writeln!(out, "{event}  {CYAN}{}{RESET}  {}", todo.id, todo.title())?;
+        } else {
+            writeln!(out, "{event}  {}  {}", todo.id, todo.title())?;
+        }
     }
     Ok(())
 }
 
 /// ANSI escape helpers — only used when stdout is a TTY.
-const DIM: &str = "\x1b[2m";
-const CYAN: &str = "\x1b[36m";
-const MAGENTA: &str = "\x1b[35m";
-const RESET: &str = "\x1b[0m";
+pub(crate) const DIM: &str = "\x1b[2m";
+pub(crate) const CYAN: &str = "\x1b[36m";
+pub(crate) const MAGENTA: &str = "\x1b[35m";
+pub(crate) const YELLOW: &str = "\x1b[33m";
+pub(crate) const RESET: &str = "\x1b[0m";
+
+/// The current user, or an error explaining how to configure one.
+pub fn current_user(store: &Store) -> Result<String> {
+    store.current_user().ok_or_else(|| {
+        anyhow!(
+            "no current user; set $TDO_USER, `user` in .todo/config.yml, or git config user.name"
+        )
+    })
+}
 
-/// Print todos to stdout, with color when connected to a terminal.
+/// Print the todos `filter` selects to stdout, one line each in `format`
+/// or the default layout, with color when connected to a terminal.
 ///
-/// Garbage-collects done todos whose `done_at` is more than 7 days ago,
-/// and warns (on stderr) about assigned todos whose `assigned_at` is more
-/// than 7 days ago.
-pub fn list_todos(store: &mut Store, all: bool) -> Result<()> {
+/// Garbage-collects done and cancelled todos closed more than 7 days ago,
+/// except those `filter` asks for, and applies the stale-assignment policy. With the default `warn` policy,
+/// stale assignments are reported on stderr when it is a terminal.
+pub fn list_todos(store: &mut Store, filter: &Filter, format: Option<&LineFormat>) -> Result<()> {
     let now = jiff::Zoned::now();
     let one_week = jiff::SignedDuration::from_hours(7 * 24);
 
-    // Garbage-collect stale done todos.
-    let stale_done_ids: Vec<(String, String)> = store
+    // Garbage-collect stale done and cancelled todos, keeping the ones the
+    // filter selects so that a query never deletes what it asked for.
+    let stale_closed: Vec<(String, String, Status)> = store
         .list_all()
         .iter()
-        .filter(|t| t.frontmatter.status == Status::Done)
+        .filter(|t| !filter.matches(t, now.date()))
         .filter_map(|t| {
-            let done_at = t.frontmatter.done_at.as_ref()?;
-            let done_zoned = done_at.to_zoned(jiff::tz::TimeZone::system()).ok()?;
-            if now.duration_since(&done_zoned) > one_week {
-                Some((t.id.clone(), t.frontmatter.title.clone()))
+            let closed_at = match t.frontmatter.status {
+                Status::Done => t.frontmatter.done_at.as_ref()?,
+                Status::Cancelled => t.frontmatter.cancelled_at.as_ref()?,
+                _ => return None,
+            };
+            let closed_zoned = closed_at.to_zoned(jiff::tz::TimeZone::system()).ok()?;
+            if now.duration_since(&closed_zoned) > one_week {
+                Some((
+                    t.id.clone(),
+                    t.frontmatter.title.clone(),
+                    t.frontmatter.status.clone(),
+                ))
             } else {
                 None
             }
         })
         .collect();
-    for (id, title) in &stale_done_ids {
+    for (id, title, status) in &stale_closed {
         store.delete(id)?;
-        eprintln!("♻ gc: removed done todo {id}  {title}");
+        eprintln!("♻ gc: removed {status} todo {id}  {title}");
+    }
+    if !stale_closed.is_empty() {
+        store.commit(&format!(
+            "tdo: gc {} closed todo{}",
+            stale_closed.len(),
+            if stale_closed.len() == 1 { "" } else { "s" }
+        ));
     }
 
-    // Warn about stale assigned todos.
-    let stale_assigned: Vec<(String, String)> = store
-        .list_all()
-        .iter()
-        .filter(|t| t.is_open() && t.is_assigned())
-        .filter_map(|t| {
-            let at = t.frontmatter.assigned_at.as_ref()?;
-            let at_zoned = at.to_zoned(jiff::tz::TimeZone::system()).ok()?;
-            if now.duration_since(&at_zoned) > one_week {
-                Some((t.id.clone(), t.frontmatter.title.clone()))
-            } else {
-                None
-            }
-        })
-        .collect();
-    if !stale_assigned.is_empty() {
-        for (id, title) in &stale_assigned {
-            eprintln!("⚠ stale assignment: {id}  {title} (assigned >7 days ago)");
+    // Handle stale assignments.
+    for todo in enforce_stale_policy(store)? {
+        report_enforced(&todo);
+    }
+    if store.config.stale_action == StaleAction::Warn && io::stderr().is_terminal() {
+        let local = now.datetime();
+        for todo in stale_todos(store, local) {
+            let days = days_assigned(todo, local);
+            eprintln!(
+                "⚠ stale assignment: {}  {} (assigned {days} days ago)",
+                todo.id,
+                todo.title()
+            );
         }
     }
 
     // Print the list.
     let stdout = io::stdout();
     let mut out = stdout.lock();
-    let color = stdout_is_tty();
-    if all {
-        for todo in store.list_all() {
-            write_todo_line(&mut out, todo, color)?;
-        }
-    } else {
-        for todo in store.list_open() {
-            write_todo_line(&mut out, todo, color)?;
+    let color = use_color();
+    for todo in filter.apply(store.list_all(), now.date()) {
+        match format {
+            Some(format) => {
+                let line = format.render(todo, &store.config, now.datetime(), color);
+                writeln!(out, "{line}")?;
+            }
+            None => write_todo_line(&mut out, todo, color, &store.config)?,
         }
     }
     Ok(())
 }
 
-fn write_todo_line(out: &mut impl Write, todo: &Todo, color: bool) -> Result<()> {
+fn write_todo_line(out: &mut impl Write, todo: &Todo, color: bool, config: &Config) -> Result<()> {
     let assigned_suffix = todo.assigned_suffix();
+    let tags_suffix: String = todo
+        .frontmatter
+        .tags
+        .iter()
+        .map(|tag| format!(" #{tag}"))
+        .collect();
+    let mut due_suffix = todo
+        .frontmatter
+        .due
+        .map(|due| format!(" (due {due})"))
+        .unwrap_or_default();
+    if let Some(until) = todo.frontmatter.deferred_until
+        && todo.is_snoozed(now().date())
+    {
+        due_suffix.push_str(&format!(" (snoozed until {until})"));
+    }
+    let status = &todo.frontmatter.status;
     if todo.is_open() {
+        // Open todos in a workflow state other than `open` show it as a label.
+        let label = match status {
+            Status::Open => String::new(),
+            other => format!("[{other}] "),
+        };
         if color {
-            write!(out, "{CYAN}{}{RESET}  {}", todo.id, todo.title())?;
+            write!(out, "{CYAN}{}{RESET}  ", todo.id)?;
+            match config.status_color(status) {
+                Some(c) if !label.is_empty() => write!(out, "{}{label}{RESET}", ansi_color(c))?,
+                _ => write!(out, "{label}")?,
+            }
+            write!(out, "{}", todo.title())?;
+            if !tags_suffix.is_empty() {
+                write!(out, "{DIM}{tags_suffix}{RESET}")?;
+            }
+            if !due_suffix.is_empty() {
+                write!(out, "{YELLOW}{due_suffix}{RESET}")?;
+            }
             if !assigned_suffix.is_empty() {
                 write!(out, "{MAGENTA}{assigned_suffix}{RESET}")?;
             }
             writeln!(out)?;
         } else {
-            writeln!(out, "{}  {}{}", todo.id, todo.title(), assigned_suffix)?;
+            writeln!(
+                out,
+                "{}  {label}{}{tags_suffix}{due_suffix}{assigned_suffix}",
+                todo.id,
+                todo.title()
+            )?;
         }
     } else if color {
         writeln!(
             out,
-            "{DIM}{}  [done] {}{assigned_suffix}{RESET}",
+            "{DIM}{}  [{status}] {}{assigned_suffix}{RESET}",
             todo.id,
             todo.title()
         )?;
     } else {
         writeln!(
             out,
-            "{}  [done] {}{}",
+            "{}  [{status}] {}{}",
             todo.id,
             todo.title(),
             assigned_suffix
@@ -265,6 +1223,24 @@ fn write_todo_line(out: &mut impl Write, todo: &Todo, color: bool) -> Result<()>
     Ok(())
 }
 
+/// ANSI escape for a configured status color.
+pub(crate) fn ansi_color(color: StatusColor) -> &'static str {
+    match color {
+        StatusColor::Red => "\x1b[31m",
+        StatusColor::Green => "\x1b[32m",
+        StatusColor::Yellow => YELLOW,
+        StatusColor::Blue => "\x1b[34m",
+        StatusColor::Magenta => MAGENTA,
+        StatusColor::Cyan => CYAN,
+        StatusColor::Gray => "\x1b[90m",
+    }
+}
+
+/// Commit a single-todo change, e.g. "tdo: done a3f9 fix the login bug".
+fn commit_todo(store: &Store, verb: &str, todo: &Todo) {
+    store.commit(&format!("tdo: {verb} {} {}", todo.id, todo.title()));
+}
+
 fn resolve_editor() -> String {
     std::env::var("VISUAL")
         .ok()
@@ -0,0 +1,350 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow, bail};
+use jiff::ToSpan;
+use jiff::civil::{Date, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// How often a recurring todo comes back, stored as `recur:` in frontmatter.
+///
+/// Accepts `daily`, `weekly`, `monthly`, `yearly`, `every N days|weeks|months|years`,
+/// or a subset of iCalendar RRULE: `FREQ`, `INTERVAL`, `BYDAY` (weekly only)
+/// and a single `BYMONTHDAY` (monthly only), e.g. `FREQ=WEEKLY;BYDAY=MO,TH`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Recur {
+    freq: Freq,
+    interval: i64,
+    /// Weekdays for weekly rules, sorted from Monday.
+    by_day: Vec<Weekday>,
+    by_month_day: Option<i8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Freq {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Freq {
+    fn parse(s: &str) -> Option<Freq> {
+        match s.to_ascii_lowercase().trim_end_matches('s') {
+            "daily" | "day" => Some(Freq::Daily),
+            "weekly" | "week" => Some(Freq::Weekly),
+            "monthly" | "month" => Some(Freq::Monthly),
+            "yearly" | "year" => Some(Freq::Yearly),
+            _ => None,
+        }
+    }
+
+    fn keyword(self) -> &'static str {
+        match self {
+            Freq::Daily => "daily",
+            Freq::Weekly => "weekly",
+            Freq::Monthly => "monthly",
+            Freq::Yearly => "yearly",
+        }
+    }
+
+    fn unit(self) -> &'static str {
+        match self {
+            Freq::Daily => "days",
+            Freq::Weekly => "weeks",
+            Freq::Monthly => "months",
+            Freq::Yearly => "years",
+        }
+    }
+}
+
+const WEEKDAYS: [(&str, Weekday); 7] = [
+    ("MO", Weekday::Monday),
+    ("TU", Weekday::Tuesday),
+    ("WE", Weekday::Wednesday),
+    ("TH", Weekday::Thursday),
+    ("FR", Weekday::Friday),
+    ("SA", Weekday::Saturday),
+    ("SU", Weekday::Sunday),
+];
+
+impl Recur {
+    /// The first occurrence strictly after `date`.
+    pub fn next_after(&self, date: Date) -> Date {
+        let n = self.interval;
+        match self.freq {
+            Freq::Daily => date.saturating_add(n.days()),
+            Freq::Weekly if self.by_day.is_empty() => date.saturating_add(n.weeks()),
+            Freq::Weekly => {
+                let offset = date.weekday().to_monday_zero_offset();
+                if let Some(day) = self
+                    .by_day
+                    .iter()
+                    .find(|d| d.to_monday_zero_offset() > offset)
+                {
+                    let ahead = day.to_monday_zero_offset() - offset;
+                    return date.saturating_add(i64::from(ahead).days());
+                }
+                let monday = date.saturating_sub(i64::from(offset).days());
+                let first = self.by_day[0].to_monday_zero_offset();
+                monday
+                    .saturating_add(n.weeks())
+                    .saturating_add(i64::from(first).days())
+            }
+            Freq::Monthly => match self.by_month_day {
+                None => date.saturating_add(n.months()),
+                Some(day) => {
+                    let this_month = day_in_month(date.first_of_month(), day);
+                    if this_month > date {
+                        this_month
+                    } else {
+                        day_in_month(date.first_of_month().saturating_add(n.months()), day)
+                    }
+                }
+            },
+            Freq::Yearly => date.saturating_add(n.years()),
+        }
+    }
+
+    /// This rule with a plain monthly recurrence pinned to `date`'s day of
+    /// the month, so a todo due on the 31st comes back on the 28th in
+    /// February and on the 31st again in March instead of drifting.
+    pub fn anchored_at(&self, date: Date) -> Recur {
+        let mut recur = self.clone();
+        if recur.freq == Freq::Monthly && recur.by_month_day.is_none() {
+            recur.by_month_day = Some(date.day());
+        }
+        recur
+    }
+
+    /// `weekly`, `every week`, `every 2 weeks`.
+    fn parse_words(s: &str) -> Result<Recur> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let (interval, freq) = match words.as_slice() {
+            [freq] => (1, Freq::parse(freq)),
+            ["every", unit] => (1, Freq::parse(unit)),
+            ["every", n, unit] => (parse_interval(n)?, Freq::parse(unit)),
+            _ => (1, None),
+        };
+        let freq = freq.ok_or_else(|| anyhow!("unknown rule"))?;
+        Ok(Recur {
+            freq,
+            interval,
+            by_day: vec![],
+            by_month_day: None,
+        })
+    }
+
+    fn parse_rrule(s: &str) -> Result<Recur> {
+        let mut freq = None;
+        let mut interval = 1;
+        let mut by_day = vec![];
+        let mut by_month_day = None;
+        for part in s.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow!("expected KEY=VALUE, got '{part}'"))?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    freq = Some(
+                        Freq::parse(value).ok_or_else(|| anyhow!("unsupported FREQ '{value}'"))?,
+                    )
+                }
+                "INTERVAL" => interval = parse_interval(value)?,
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        let (_, weekday) = WEEKDAYS
+                            .iter()
+                            .find(|(code, _)| code.eq_ignore_ascii_case(day))
+                            .ok_or_else(|| anyhow!("unknown BYDAY '{day}' (expected MO..SU)"))?;
+                        by_day.push(*weekday);
+                    }
+                }
+                "BYMONTHDAY" => {
+                    let day: i8 = value
+                        .parse()
+                        .ok()
+                        .filter(|d| (1..=31).contains(d))
+                        .ok_or_else(|| anyhow!("BYMONTHDAY must be a single day 1-31"))?;
+                    by_month_day = Some(day);
+                }
+                other => bail!("unsupported rule part '{other}'"),
+            }
+        }
+        let freq = freq.ok_or_else(|| anyhow!("missing FREQ"))?;
+        if !by_day.is_empty() && freq != Freq::Weekly {
+            bail!("BYDAY is only supported with FREQ=WEEKLY");
+        }
+        if by_month_day.is_some() && freq != Freq::Monthly {
+            bail!("BYMONTHDAY is only supported with FREQ=MONTHLY");
+        }
+        by_day.sort_by_key(|d| d.to_monday_zero_offset());
+        by_day.dedup();
+        Ok(Recur {
+            freq,
+            interval,
+            by_day,
+            by_month_day,
+        })
+    }
+}
+
+fn parse_interval(s: &str) -> Result<i64> {
+    s.parse()
+        .ok()
+        .filter(|n| (1..=1000).contains(n))
+        .ok_or_else(|| anyhow!("interval must be a number from 1 to 1000, got '{s}'"))
+}
+
+/// `day` of the month starting at `first`, clamped to the month's last day.
+fn day_in_month(first: Date, day: i8) -> Date {
+    first.saturating_add(i64::from(day.min(first.days_in_month()) - 1).days())
+}
+
+impl FromStr for Recur {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Recur> {
+        let s = s.trim();
+        let rule = s
+            .get(..6)
+            .filter(|p| p.eq_ignore_ascii_case("RRULE:"))
+            .map_or(s, |_| &s[6..]);
+        let result = if rule.contains('=') {
+            Recur::parse_rrule(rule)
+        } else {
+            Recur::parse_words(rule)
+        };
+        result.map_err(|e| {
+            anyhow!("invalid recurrence '{s}': {e} (expected e.g. weekly, every 2 weeks, FREQ=WEEKLY;BYDAY=MO)")
+        })
+    }
+}
+
+impl fmt::Display for Recur {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.by_day.is_empty() && self.by_month_day.is_none() {
+            return if self.interval == 1 {
+                f.write_str(self.freq.keyword())
+            } else {
+                write!(f, "every {} {}", self.interval, self.freq.unit())
+            };
+        }
+        write!(f, "FREQ={}", self.freq.keyword().to_ascii_uppercase())?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<&str> = self
+                .by_day
+                .iter()
+                .filter_map(|d| WEEKDAYS.iter().find(|(_, w)| w == d).map(|(code, _)| *code))
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if let Some(day) = self.by_month_day {
+            write!(f, ";BYMONTHDAY={day}")?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<String> for Recur {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Recur> {
+        s.parse()
+    }
+}
+
+impl From<Recur> for String {
+    fn from(r: Recur) -> String {
+        r.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn next(rule: &str, date: &str) -> String {
+        let recur: Recur = rule.parse().unwrap();
+        recur.next_after(date.parse().unwrap()).to_string()
+    }
+
+    #[test]
+    fn simple_frequencies() {
+        assert_eq!(next("daily", "2026-02-28"), "2026-03-01");
+        assert_eq!(next("weekly", "2026-02-18"), "2026-02-25");
+        assert_eq!(next("every 2 weeks", "2026-02-18"), "2026-03-04");
+        assert_eq!(next("monthly", "2026-01-31"), "2026-02-28");
+        assert_eq!(next("yearly", "2028-02-29"), "2029-02-28");
+    }
+
+    #[test]
+    fn weekly_by_day() {
+        // 2026-02-18 is a Wednesday.
+        assert_eq!(next("FREQ=WEEKLY;BYDAY=MO,TH", "2026-02-18"), "2026-02-19");
+        assert_eq!(next("FREQ=WEEKLY;BYDAY=MO,TH", "2026-02-19"), "2026-02-23");
+        assert_eq!(
+            next("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO", "2026-02-18"),
+            "2026-03-02"
+        );
+    }
+
+    #[test]
+    fn monthly_by_month_day_clamps() {
+        assert_eq!(
+            next("FREQ=MONTHLY;BYMONTHDAY=15", "2026-02-10"),
+            "2026-02-15"
+        );
+        assert_eq!(
+            next("FREQ=MONTHLY;BYMONTHDAY=31", "2026-01-31"),
+            "2026-02-28"
+        );
+        assert_eq!(
+            next("FREQ=MONTHLY;BYMONTHDAY=31", "2026-02-28"),
+            "2026-03-31"
+        );
+    }
+
+    #[test]
+    fn monthly_keeps_its_anchor_day() {
+        let jan31: Date = "2026-01-31".parse().unwrap();
+        let recur = "monthly".parse::<Recur>().unwrap().anchored_at(jan31);
+        let feb = recur.next_after(jan31);
+        assert_eq!(feb.to_string(), "2026-02-28");
+        assert_eq!(recur.next_after(feb).to_string(), "2026-03-31");
+        assert_eq!(recur.to_string(), "FREQ=MONTHLY;BYMONTHDAY=31");
+    }
+
+    #[test]
+    fn display_is_canonical_and_parses_back() {
+        for (input, shown) in [
+            ("Weekly", "weekly"),
+            ("every 1 day", "daily"),
+            ("every 3 months", "every 3 months"),
+            ("FREQ=MONTHLY", "monthly"),
+            ("freq=weekly;byday=th,mo", "FREQ=WEEKLY;BYDAY=MO,TH"),
+        ] {
+            let recur: Recur = input.parse().unwrap();
+            assert_eq!(recur.to_string(), shown);
+            assert_eq!(shown.parse::<Recur>().unwrap(), recur);
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_rules() {
+        for rule in [
+            "fortnightly",
+            "every 0 days",
+            "FREQ=HOURLY",
+            "FREQ=DAILY;BYDAY=MO",
+            "FREQ=WEEKLY;COUNT=3",
+            "INTERVAL=2",
+        ] {
+            assert!(rule.parse::<Recur>().is_err(), "{rule}");
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use anyhow::Result;
+use jiff::civil::{Date, DateTime};
+use jiff::{SignedDuration, ToSpan};
+use serde::Serialize;
+
+use crate::ops::{CYAN, RESET};
+use crate::storage::Store;
+use crate::todo::{Status, Todo};
+use crate::util::{format_duration, now, parse_since, use_color};
+
+/// Store-wide summary printed by `tdo report`.
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub open: usize,
+    pub done: usize,
+    pub cancelled: usize,
+    /// Open todos that are assigned.
+    pub assigned: usize,
+    /// Sum of estimates over open todos.
+    pub open_estimate_secs: i64,
+    /// Mean time from `created` to `done_at`, over done todos.
+    pub avg_cycle_time_secs: Option<i64>,
+    /// Most recent ISO weeks, oldest first.
+    pub weeks: Vec<WeekStats>,
+    pub assignees: Vec<AssigneeLoad>,
+    pub stale: Vec<StaleItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeekStats {
+    /// ISO week, e.g. `2026-W08`.
+    pub week: String,
+    pub created: usize,
+    pub completed: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssigneeLoad {
+    /// Empty for todos assigned without a name. A todo with several
+    /// assignees counts towards each of them.
+    pub name: String,
+    pub open: usize,
+    pub estimate_secs: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StaleItem {
+    pub id: String,
+    pub title: String,
+    pub assigned: String,
+    pub days: i64,
+}
+
+/// Summarise `todos` as of `now`, with created/completed counts for the
+/// last `weeks` ISO weeks. Assignments older than `stale_after` are stale.
+pub fn summarize(
+    todos: &[Todo],
+    weeks: usize,
+    now: DateTime,
+    stale_after: SignedDuration,
+) -> Summary {
+    let open: Vec<&Todo> = todos.iter().filter(|t| t.is_open()).collect();
+    let done: Vec<&Todo> = todos
+        .iter()
+        .filter(|t| t.frontmatter.status == Status::Done)
+        .collect();
+
+    let open_estimate = open
+        .iter()
+        .filter_map(|t| t.frontmatter.estimate)
+        .fold(SignedDuration::ZERO, |total, e| total + e);
+
+    let cycle_times: Vec<SignedDuration> = done
+        .iter()
+        .filter_map(|t| Some(t.frontmatter.done_at?.duration_since(t.frontmatter.created)))
+        .collect();
+    let avg_cycle_time_secs = (!cycle_times.is_empty()).then(|| {
+        let total: i64 = cycle_times.iter().map(|d| d.as_secs()).sum();
+        total / cycle_times.len() as i64
+    });
+
+    let this_week = week_start(now.date());
+    let mut week_stats: Vec<WeekStats> = (0..weeks)
+        .rev()
+        .map(|i| WeekStats {
+            week: week_label(this_week.saturating_sub((i as i64 * 7).days())),
+            created: 0,
+            completed: 0,
+        })
+        .collect();
+    let mut count = |dt: DateTime, completed: bool| {
+        let label = week_label(week_start(dt.date()));
+        if let Some(stats) = week_stats.iter_mut().find(|w| w.week == label) {
+            if completed {
+                stats.completed += 1;
+            } else {
+                stats.created += 1;
+            }
+        }
+    };
+    for todo in todos {
+        count(todo.frontmatter.created, false);
+        if let Some(done_at) = todo.frontmatter.done_at {
+            count(done_at, true);
+        }
+    }
+
+    let mut by_assignee: BTreeMap<String, AssigneeLoad> = BTreeMap::new();
+    for todo in &open {
+        for name in &todo.frontmatter.assigned {
+            let load = by_assignee.entry(name.clone()).or_insert(AssigneeLoad {
+                name: name.clone(),
+                open: 0,
+                estimate_secs: 0,
+            });
+            load.open += 1;
+            load.estimate_secs += todo.frontmatter.estimate.map_or(0, |e| e.as_secs());
+        }
+    }
+    let mut assignees: Vec<AssigneeLoad> = by_assignee.into_values().collect();
+    assignees.sort_by_key(|a| std::cmp::Reverse(a.open));
+
+    let stale = open
+        .iter()
+        .filter(|t| t.is_stale(now, stale_after))
+        .filter_map(|t| {
+            let age = now.duration_since(t.frontmatter.assigned_at?);
+            Some(StaleItem {
+                id: t.id.clone(),
+                title: t.title().to_string(),
+                assigned: t.assignees(),
+                days: age.as_hours() / 24,
+            })
+        })
+        .collect();
+
+    Summary {
+        open: open.len(),
+        done: done.len(),
+        cancelled: todos
+            .iter()
+            .filter(|t| t.frontmatter.status == Status::Cancelled)
+            .count(),
+        assigned: open.iter().filter(|t| t.is_assigned()).count(),
+        open_estimate_secs: open_estimate.as_secs(),
+        avg_cycle_time_secs,
+        weeks: week_stats,
+        assignees,
+        stale,
+    }
+}
+
+/// Print the store summary as text, or as JSON with `json`.
+pub fn report_summary(store: &Store, weeks: usize, json: bool) -> Result<()> {
+    let summary = summarize(store.list_all(), weeks, now(), store.config.stale_after);
+    let mut out = io::stdout().lock();
+    if json {
+        serde_json::to_writer_pretty(&mut out, &summary)?;
+        writeln!(out)?;
+        return Ok(());
+    }
+
+    let color = use_color();
+    let secs = SignedDuration::from_secs;
+    writeln!(
+        out,
+        "open:       {} ({} assigned)",
+        summary.open, summary.assigned
+    )?;
+    writeln!(out, "done:       {}", summary.done)?;
+    if summary.cancelled > 0 {
+        writeln!(out, "cancelled:  {}", summary.cancelled)?;
+    }
+    if summary.open_estimate_secs > 0 {
+        writeln!(
+            out,
+            "estimated:  {} open",
+            format_duration(secs(summary.open_estimate_secs))
+        )?;
+    }
+    if let Some(avg) = summary.avg_cycle_time_secs {
+        writeln!(
+            out,
+            "cycle time: {} average over {} done",
+            format_duration(secs(avg)),
+            summary.done
+        )?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "week      created  done")?;
+    for week in &summary.weeks {
+        writeln!(
+            out,
+            "{}  {:>7}  {:>4}",
+            week.week, week.created, week.completed
+        )?;
+    }
+
+    if !summary.assignees.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "assignees:")?;
+        for load in &summary.assignees {
+            let name = if load.name.is_empty() {
+                "(unnamed)"
+            } else {
+                &load.name
+            };
+            write!(out, "  {name:<12} {:>3} open", load.open)?;
+            if load.estimate_secs > 0 {
+                write!(
+                    out,
+                    "  {} estimated",
+                    format_duration(secs(load.estimate_secs))
+                )?;
+            }
+            writeln!(out)?;
+        }
+    }
+
+    if !summary.stale.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "stale assignments:")?;
+        for item in &summary.stale {
+            let id = if color {
+                format!("{CYAN}{}{RESET}", item.id)
+            } else {
+                item.id.clone()
+            };
+            writeln!(
+                out,
+                "  {id}  {} (assigned {} days ago)",
+                item.title, item.days
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Print time tracked per todo since `since` (or ever), largest first.
+pub fn report_time(store: &Store, since: Option<&str>) -> Result<()> {
+    let since = since.map(parse_since).transpose()?;
+    let now = now();
+    let mut rows: Vec<(&Todo, SignedDuration)> = store
+        .list_all()
+        .iter()
+        .map(|t| {
+            let spent = t
+                .frontmatter
+                .work
+                .iter()
+                .map(|w| {
+                    let start = since.map_or(w.start, |s| w.start.max(s));
+                    w.end.unwrap_or(now).duration_since(start)
+                })
+                .filter(|d| d.is_positive())
+                .fold(SignedDuration::ZERO, |total, d| total + d);
+            (t, spent)
+        })
+        .filter(|(_, spent)| !spent.is_zero())
+        .collect();
+    rows.sort_by_key(|(_, spent)| std::cmp::Reverse(*spent));
+
+    let color = use_color();
+    let mut out = io::stdout().lock();
+    let mut total = SignedDuration::ZERO;
+    for (todo, spent) in &rows {
+        total += *spent;
+        let running = if todo.running_since().is_some() {
+            " (running)"
+        } else {
+            ""
+        };
+        if color {
+            writeln!(
+                out,
+                "{CYAN}{}{RESET}  {:>8}  {}{running}",
+                todo.id,
+                format_duration(*spent),
+                todo.title()
+            )?;
+        } else {
+            writeln!(
+                out,
+                "{}  {:>8}  {}{running}",
+                todo.id,
+                format_duration(*spent),
+                todo.title()
+            )?;
+        }
+    }
+    writeln!(out, "total {:>8}", format_duration(total))?;
+    Ok(())
+}
+
+/// The Monday starting the ISO week that contains `date`.
+fn week_start(date: Date) -> Date {
+    let offset = date.weekday().to_monday_zero_offset();
+    date.saturating_sub(i64::from(offset).days())
+}
+
+fn week_label(date: Date) -> String {
+    let week = date.iso_week_date();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::new_frontmatter;
+    use crate::todo::Frontmatter;
+
+    fn todo(id: &str, created: &str, done_at: Option<&str>, assigned: Option<&str>) -> Todo {
+        Todo {
+            id: id.to_string(),
+            filename: format!("{id}.md"),
+            frontmatter: Frontmatter {
+                status: if done_at.is_some() {
+                    Status::Done
+                } else {
+                    Status::Open
+                },
+                assigned: assigned.into_iter().map(|a| a.to_string()).collect(),
+                assigned_at: assigned.map(|_| created.parse().unwrap()),
+                done_at: done_at.map(|d| d.parse().unwrap()),
+                estimate: Some(SignedDuration::from_hours(2)),
+                ..new_frontmatter(&format!("todo {id}"), created.parse().unwrap(), None)
+            },
+            body: None,
+        }
+    }
+
+    #[test]
+    fn summary_counts_weeks_cycle_time_and_load() {
+        // 2026-02-18 is a Wednesday in ISO week 8.
+        let now: DateTime = "2026-02-18T12:00:00".parse().unwrap();
+        let todos = vec![
+            todo(
+                "aaaa",
+                "2026-02-02T09:00:00",
+                Some("2026-02-04T09:00:00"),
+                None,
+            ),
+            todo("bbbb", "2026-02-16T09:00:00", None, Some("alice")),
+            todo("cccc", "2026-02-17T09:00:00", None, Some("alice")),
+            todo("dddd", "2026-02-01T09:00:00", None, Some("bob")),
+        ];
+        let summary = summarize(&todos, 3, now, SignedDuration::from_hours(7 * 24));
+
+        assert_eq!(summary.open, 3);
+        assert_eq!(summary.done, 1);
+        assert_eq!(summary.assigned, 3);
+        assert_eq!(summary.open_estimate_secs, 6 * 3600);
+        assert_eq!(summary.avg_cycle_time_secs, Some(2 * 24 * 3600));
+
+        let weeks: Vec<(&str, usize, usize)> = summary
+            .weeks
+            .iter()
+            .map(|w| (w.week.as_str(), w.created, w.completed))
+            .collect();
+        assert_eq!(
+            weeks,
+            vec![("2026-W06", 1, 1), ("2026-W07", 0, 0), ("2026-W08", 2, 0)]
+        );
+
+        assert_eq!(summary.assignees[0].name, "alice");
+        assert_eq!(summary.assignees[0].open, 2);
+        assert_eq!(summary.assignees[0].estimate_secs, 4 * 3600);
+
+        assert_eq!(summary.stale.len(), 1);
+        assert_eq!(summary.stale[0].id, "dddd");
+        assert_eq!(summary.stale[0].days, 17);
+    }
+}
@@ -1,52 +1,110 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs::{self, File, TryLockError};
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, anyhow};
 
+use crate::config::Config;
+use crate::git;
 use crate::todo::{self, Frontmatter, Todo};
-use crate::util::{generate_id, slugify};
+use crate::util::{generate_id, now, slugify};
+
+/// Name of the advisory lock file inside the todo directory.
+pub const LOCK_FILE: &str = ".lock";
 
 pub struct Store {
     dir: PathBuf,
     _lock_file: File,
     cache: Vec<Todo>,
+    pub config: Config,
     /// Number of `.md` files that failed to parse during load.
     pub skipped: usize,
+    /// Groups of filenames that share the same ID, found during load.
+    pub duplicates: Vec<Vec<String>>,
 }
 
 impl Store {
     /// Open a store, creating the directory if it doesn't exist.
     /// Acquires an advisory lock to prevent concurrent access.
     pub fn open(dir: &Path) -> Result<Self> {
+        Store::open_with(dir, false)
+    }
+
+    /// Like `open`, but waits for another tdo process to release the lock
+    /// instead of failing.
+    pub fn open_waiting(dir: &Path) -> Result<Self> {
+        Store::open_with(dir, true)
+    }
+
+    fn open_with(dir: &Path, wait: bool) -> Result<Self> {
         fs::create_dir_all(dir)
             .with_context(|| format!("failed to create todo directory: {}", dir.display()))?;
 
-        let lock_path = dir.join(".lock");
+        let lock_path = dir.join(LOCK_FILE);
         let lock_file = fs::OpenOptions::new()
             .create(true)
+            .truncate(false)
             .write(true)
             .open(&lock_path)
             .with_context(|| format!("failed to create lock file: {}", lock_path.display()))?;
-        lock_file.try_lock().map_err(|e| match e {
-            TryLockError::WouldBlock => {
-                anyhow!("another tdo process is using {}", dir.display())
-            }
-            TryLockError::Error(io_err) => {
-                anyhow::Error::from(io_err).context("failed to acquire lock")
-            }
-        })?;
+        if wait {
+            lock_file.lock().context("failed to acquire lock")?;
+        } else {
+            lock_file.try_lock().map_err(|e| match e {
+                TryLockError::WouldBlock => {
+                    anyhow!("another tdo process is using {}", dir.display())
+                }
+                TryLockError::Error(io_err) => {
+                    anyhow::Error::from(io_err).context("failed to acquire lock")
+                }
+            })?;
+        }
 
-        let (cache, skipped) = load_all_todos(dir)?;
+        let config = Config::load(dir)?;
+        let (cache, skipped) = load_all_todos(dir, &config)?;
+        let duplicates = find_duplicates(&cache);
 
         Ok(Store {
             dir: dir.to_path_buf(),
             _lock_file: lock_file,
             cache,
+            config,
             skipped,
+            duplicates,
         })
     }
 
+    /// Re-read the config and every todo from disk, e.g. after a git pull.
+    pub fn reload(&mut self) -> Result<()> {
+        self.config = Config::load(&self.dir)?;
+        let (cache, skipped) = load_all_todos(&self.dir, &self.config)?;
+        self.duplicates = find_duplicates(&cache);
+        self.cache = cache;
+        self.skipped = skipped;
+        Ok(())
+    }
+
+    /// Commit the todo directory with git if `auto_commit` is enabled.
+    /// Failures only warn: the change itself has already been written.
+    pub fn commit(&self, message: &str) {
+        if !self.config.auto_commit {
+            return;
+        }
+        if let Err(e) = git::commit_dir(&self.dir, message) {
+            eprintln!("warning: auto-commit failed: {e:#}");
+        }
+    }
+
+    /// The current user: `$TDO_USER`, else `user` from the config, else
+    /// `git config user.name`.
+    pub fn current_user(&self) -> Option<String> {
+        std::env::var("TDO_USER")
+            .ok()
+            .filter(|u| !u.trim().is_empty())
+            .or_else(|| self.config.user.clone())
+            .or_else(|| git::user_name(&self.dir))
+    }
+
     /// Resolve the todo directory: use the override if provided, otherwise `.todo/` in cwd.
     pub fn resolve_dir(override_dir: Option<&Path>) -> PathBuf {
         match override_dir {
@@ -55,13 +113,27 @@ impl Store {
         }
     }
 
+    /// The directory this store reads from.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
     /// All todos, sorted by created timestamp ascending.
     pub fn list_all(&self) -> &[Todo] {
         &self.cache
     }
 
-    /// Only open todos (references into the cache).
+    /// Open todos that aren't snoozed (references into the cache).
     pub fn list_open(&self) -> Vec<&Todo> {
+        let today = now().date();
+        self.cache
+            .iter()
+            .filter(|t| t.is_open() && !t.is_snoozed(today))
+            .collect()
+    }
+
+    /// Open todos including snoozed ones.
+    pub fn list_open_with_snoozed(&self) -> Vec<&Todo> {
         self.cache.iter().filter(|t| t.is_open()).collect()
     }
 
@@ -75,12 +147,7 @@ impl Store {
     pub fn create(&mut self, fm: &Frontmatter, body: Option<&str>) -> Result<String> {
         let existing_ids: HashSet<String> = self.cache.iter().map(|t| t.id.clone()).collect();
         let id = generate_id(|candidate| existing_ids.contains(candidate))?;
-        let slug = slugify(&fm.title);
-        let filename = if slug.is_empty() {
-            format!("{id}.md")
-        } else {
-            format!("{id}-{slug}.md")
-        };
+        let filename = todo_filename(&id, &fm.title);
         let content = todo::render_file(fm, body)?;
         let path = self.dir.join(&filename);
         fs::write(&path, &content)
@@ -92,8 +159,7 @@ impl Store {
             frontmatter: fm.clone(),
             body: body.map(|s| s.to_string()),
         });
-        self.cache
-            .sort_by(|a, b| a.frontmatter.created.cmp(&b.frontmatter.created));
+        self.cache.sort_by_key(|t| t.frontmatter.created);
 
         Ok(id)
     }
@@ -122,6 +188,35 @@ impl Store {
         Ok(todo)
     }
 
+    /// Give the todo stored in `filename` a fresh ID and rename its file,
+    /// keeping the slug. Returns the updated todo.
+    pub fn reid(&mut self, filename: &str) -> Result<Todo> {
+        let idx = self
+            .cache
+            .iter()
+            .position(|t| t.filename == filename)
+            .ok_or_else(|| anyhow!("no loaded todo file named '{filename}'"))?;
+        let existing_ids: HashSet<String> = self.cache.iter().map(|t| t.id.clone()).collect();
+        let id = generate_id(|candidate| existing_ids.contains(candidate))?;
+        let new_filename = reid_filename(filename, &id);
+        let from = self.dir.join(filename);
+        let to = self.dir.join(&new_filename);
+        if to.exists() {
+            return Err(anyhow!(
+                "cannot rename {filename}: {new_filename} already exists"
+            ));
+        }
+        fs::rename(&from, &to)
+            .with_context(|| format!("failed to rename {filename} to {new_filename}"))?;
+
+        let todo = &mut self.cache[idx];
+        todo.id = id;
+        todo.filename = new_filename;
+        let todo = todo.clone();
+        self.duplicates = find_duplicates(&self.cache);
+        Ok(todo)
+    }
+
     /// Return the full path for a todo file.
     pub fn path_for(&self, todo: &Todo) -> PathBuf {
         self.dir.join(&todo.filename)
@@ -133,13 +228,64 @@ impl Store {
         let path = self.dir.join(&self.cache[idx].filename);
         let raw = fs::read_to_string(&path)
             .with_context(|| format!("failed to read: {}", path.display()))?;
-        let (fm, body) = todo::parse_file(&raw)?;
+        let (fm, body) = todo::parse_file(&raw, &self.config)?;
         let cached = &mut self.cache[idx];
         cached.frontmatter = fm;
         cached.body = body;
         Ok(())
     }
 
+    /// Todos matching `query`, which is an ID prefix or a title query.
+    ///
+    /// An ID prefix wins if any ID starts with it; a leading `@` forces a
+    /// title query. Titles match, best first, exactly, by prefix, by
+    /// containing every word of the query, or by containing its characters
+    /// in order (all ignoring case); only the best kind that matches
+    /// anything counts, and open todos are preferred over closed ones.
+    /// Several matches are returned for the caller to disambiguate.
+    pub fn find_by_query(&self, query: &str) -> Result<Vec<&Todo>> {
+        if query.is_empty() {
+            return Err(anyhow!("todo id must not be empty"));
+        }
+        if !query.starts_with('@') {
+            let mut by_id: Vec<&Todo> = self
+                .cache
+                .iter()
+                .filter(|t| t.id.starts_with(query))
+                .collect();
+            // Files sharing an ID are reported when the todo is looked up.
+            by_id.dedup_by(|a, b| a.id == b.id);
+            if !by_id.is_empty() {
+                return Ok(by_id);
+            }
+        }
+        let title = title_key(query.strip_prefix('@').unwrap_or(query));
+        if title.is_empty() {
+            return Err(anyhow!("title query must not be empty"));
+        }
+        let mut best: Option<u8> = None;
+        let mut matches = vec![];
+        for todo in &self.cache {
+            let Some(rank) = title_rank(&title_key(todo.title()), &title) else {
+                continue;
+            };
+            if best.is_none_or(|best| rank < best) {
+                best = Some(rank);
+                matches.clear();
+            }
+            if best == Some(rank) {
+                matches.push(todo);
+            }
+        }
+        if matches.iter().any(|t| t.is_open()) {
+            matches.retain(|t| t.is_open());
+        }
+        if matches.is_empty() {
+            return Err(anyhow!("no todo found with id or title '{query}'"));
+        }
+        Ok(matches)
+    }
+
     fn find_index(&self, id: &str) -> Result<usize> {
         if id.is_empty() {
             return Err(anyhow!("todo id must not be empty"));
@@ -154,6 +300,20 @@ impl Store {
         match matches.len() {
             0 => Err(anyhow!("no todo found with id '{id}'")),
             1 => Ok(matches[0]),
+            _ if matches
+                .iter()
+                .all(|&i| self.cache[i].id == self.cache[matches[0]].id) =>
+            {
+                let files: Vec<&str> = matches
+                    .iter()
+                    .map(|&i| self.cache[i].filename.as_str())
+                    .collect();
+                Err(anyhow!(
+                    "id '{}' is used by multiple files: {}; run `tdo reid <filename>` to give one a new id",
+                    self.cache[matches[0]].id,
+                    files.join(", ")
+                ))
+            }
             _ => {
                 let ids: Vec<&str> = matches.iter().map(|&i| self.cache[i].id.as_str()).collect();
                 Err(anyhow!("ambiguous id '{id}': matches {}", ids.join(", ")))
@@ -162,11 +322,62 @@ impl Store {
     }
 }
 
+/// Lowercase `s` with runs of whitespace collapsed, so titles compare equal
+/// regardless of case and spacing.
+pub(crate) fn title_key(s: &str) -> String {
+    s.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// How well title `title` matches `query` (both as `title_key`s): 0 for
+/// equal, 1 for a prefix, 2 for containing every word, 3 for containing
+/// its characters in order, `None` for no match.
+fn title_rank(title: &str, query: &str) -> Option<u8> {
+    if title == query {
+        Some(0)
+    } else if title.starts_with(query) {
+        Some(1)
+    } else if query.split(' ').all(|word| title.contains(word)) {
+        Some(2)
+    } else {
+        let mut chars = title.chars();
+        query
+            .chars()
+            .filter(|c| *c != ' ')
+            .all(|q| chars.any(|c| c == q))
+            .then_some(3)
+    }
+}
+
 /// Load all valid todos from `dir`. Returns the list and the count of
 /// `.md` files that looked like todo files but failed to parse.
-fn load_all_todos(dir: &Path) -> Result<(Vec<Todo>, usize)> {
+fn load_all_todos(dir: &Path, config: &Config) -> Result<(Vec<Todo>, usize)> {
+    let (todos, invalid) = read_todos(dir, config)?;
+    for (path, e) in &invalid {
+        eprintln!("warning: skipping {}: {e}", path.display());
+    }
+    Ok((todos, invalid.len()))
+}
+
+/// Read the todos in `dir` without locking it or printing anything, e.g.
+/// for shell completion. Unreadable or invalid files are left out.
+pub fn peek_todos(dir: &Path) -> Vec<Todo> {
+    let config = Config::load(dir).unwrap_or_default();
+    read_todos(dir, &config)
+        .map(|(todos, _)| todos)
+        .unwrap_or_default()
+}
+
+/// Todo files that failed to parse, with the reason.
+type InvalidFiles = Vec<(PathBuf, anyhow::Error)>;
+
+/// Parse every todo file in `dir`, sorted by creation time, along with the
+/// files that failed to parse.
+fn read_todos(dir: &Path, config: &Config) -> Result<(Vec<Todo>, InvalidFiles)> {
     let mut todos = Vec::new();
-    let mut skipped: usize = 0;
+    let mut invalid = Vec::new();
     let entries = fs::read_dir(dir)
         .with_context(|| format!("failed to read directory: {}", dir.display()))?;
     for entry in entries {
@@ -179,7 +390,7 @@ fn load_all_todos(dir: &Path) -> Result<(Vec<Todo>, usize)> {
         if let Some(id) = id_from_filename(&filename) {
             let raw = fs::read_to_string(&path)
                 .with_context(|| format!("failed to read: {}", path.display()))?;
-            match todo::parse_file(&raw) {
+            match todo::parse_file(&raw, config) {
                 Ok((fm, body)) => {
                     todos.push(Todo {
                         id: id.to_string(),
@@ -188,19 +399,55 @@ fn load_all_todos(dir: &Path) -> Result<(Vec<Todo>, usize)> {
                         body,
                     });
                 }
-                Err(e) => {
-                    eprintln!("warning: skipping {}: {e}", path.display());
-                    skipped += 1;
-                }
+                Err(e) => invalid.push((path, e)),
             }
         }
     }
-    todos.sort_by(|a, b| a.frontmatter.created.cmp(&b.frontmatter.created));
-    Ok((todos, skipped))
+    todos.sort_by_key(|t| t.frontmatter.created);
+    Ok((todos, invalid))
+}
+
+/// Group the filenames of todos that share an ID.
+fn find_duplicates(todos: &[Todo]) -> Vec<Vec<String>> {
+    let mut by_id: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for todo in todos {
+        by_id
+            .entry(todo.id.as_str())
+            .or_default()
+            .push(todo.filename.clone());
+    }
+    by_id
+        .into_values()
+        .filter(|files| files.len() > 1)
+        .map(|mut files| {
+            files.sort();
+            files
+        })
+        .collect()
+}
+
+/// Replace the ID prefix of a todo filename, keeping the slug:
+/// `a3f9-fix-the-bug.md` becomes `<new_id>-fix-the-bug.md`.
+pub fn reid_filename(filename: &str, new_id: &str) -> String {
+    match id_from_filename(filename) {
+        Some(old_id) => format!("{new_id}{}", &filename[old_id.len()..]),
+        None => format!("{new_id}-{filename}"),
+    }
+}
+
+/// Build the filename for a new todo: `<id>-<slug>.md`, or `<id>.md` if the
+/// title slugifies to nothing.
+pub fn todo_filename(id: &str, title: &str) -> String {
+    let slug = slugify(title);
+    if slug.is_empty() {
+        format!("{id}.md")
+    } else {
+        format!("{id}-{slug}.md")
+    }
 }
 
 /// Extract the hex ID from a filename like "a3f9-fix-the-login-bug.md".
-fn id_from_filename(name: &str) -> Option<&str> {
+pub fn id_from_filename(name: &str) -> Option<&str> {
     let stem = name.strip_suffix(".md")?;
     let id_part = stem.split('-').next()?;
     if id_part.len() == 4 && id_part.chars().all(|c| c.is_ascii_hexdigit()) {
@@ -224,6 +471,25 @@ mod tests {
         assert_eq!(id_from_filename("a3f9.md"), Some("a3f9"));
     }
 
+    #[test]
+    fn reid_filename_keeps_slug() {
+        assert_eq!(
+            reid_filename("a3f9-fix-the-bug.md", "07c2"),
+            "07c2-fix-the-bug.md"
+        );
+        assert_eq!(reid_filename("a3f9.md", "07c2"), "07c2.md");
+    }
+
+    #[test]
+    fn title_queries_rank_exact_prefix_words_then_fuzzy() {
+        let rank = |title: &str, query: &str| title_rank(&title_key(title), &title_key(query));
+        assert_eq!(rank("Login  bug", "login bug"), Some(0));
+        assert_eq!(rank("Login bug on Safari", "login bug"), Some(1));
+        assert_eq!(rank("Fix the bug in login", "login bug"), Some(2));
+        assert_eq!(rank("Fix the login bug", "lgn bug"), Some(3));
+        assert_eq!(rank("Fix the login bug", "logout"), None);
+    }
+
     #[test]
     fn id_from_filename_invalid() {
         assert_eq!(id_from_filename("not-a-todo.md"), None);
@@ -0,0 +1,120 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+/// Directory inside the todo directory that holds body templates, one
+/// `<name>.md` file each.
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// Values substituted for the `{{title}}`, `{{date}}` and `{{user}}`
+/// placeholders.
+pub struct Vars<'a> {
+    pub title: &'a str,
+    pub date: String,
+    /// Empty when there is no current user.
+    pub user: String,
+}
+
+/// Names of the templates in `dir`, sorted. A missing directory has none.
+pub fn names(dir: &Path) -> Result<Vec<String>> {
+    let path = dir.join(TEMPLATES_DIR);
+    let entries = match fs::read_dir(&path) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => {
+            return Err(e).with_context(|| format!("failed to read: {}", path.display()));
+        }
+    };
+    let mut names = vec![];
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_file()
+            && path.extension().and_then(|e| e.to_str()) == Some("md")
+            && let Some(stem) = path.file_stem().and_then(|s| s.to_str())
+        {
+            names.push(stem.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Read template `name` from `dir` and fill in its placeholders.
+pub fn render_named(dir: &Path, name: &str, vars: &Vars) -> Result<String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        bail!("invalid template name '{name}'");
+    }
+    let path = dir.join(TEMPLATES_DIR).join(format!("{name}.md"));
+    let raw = match fs::read_to_string(&path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let available = names(dir)?;
+            if available.is_empty() {
+                bail!(
+                    "no template '{name}': {} has no templates",
+                    dir.join(TEMPLATES_DIR).display()
+                );
+            }
+            bail!("no template '{name}' (available: {})", available.join(", "));
+        }
+        Err(e) => return Err(e).with_context(|| format!("failed to read: {}", path.display())),
+    };
+    render(&raw, vars).with_context(|| format!("in template '{name}'"))
+}
+
+/// Substitute `{{ placeholder }}`s in `template`. Unknown placeholders are
+/// an error rather than being left in the body.
+pub fn render(template: &str, vars: &Vars) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            bail!("unclosed '{{{{'");
+        };
+        match after[..end].trim() {
+            "title" => out.push_str(vars.title),
+            "date" => out.push_str(&vars.date),
+            "user" => out.push_str(&vars.user),
+            other => bail!("unknown placeholder '{{{{{other}}}}}' (expected title, date or user)"),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> Vars<'static> {
+        Vars {
+            title: "login fails",
+            date: "2026-02-20".to_string(),
+            user: "alice".to_string(),
+        }
+    }
+
+    #[test]
+    fn placeholders_are_substituted() {
+        let body = render(
+            "# {{title}}\nReported {{ date }} by {{user}}.\n\n## Repro\n",
+            &vars(),
+        )
+        .unwrap();
+        assert_eq!(
+            body,
+            "# login fails\nReported 2026-02-20 by alice.\n\n## Repro\n"
+        );
+    }
+
+    #[test]
+    fn unknown_or_unclosed_placeholders_fail() {
+        let err = render("{{owner}}", &vars()).unwrap_err().to_string();
+        assert!(err.contains("'{{owner}}'"), "{err}");
+        assert!(render("{{title", &vars()).is_err());
+    }
+}
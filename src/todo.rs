@@ -1,25 +1,230 @@
+use std::fmt;
+
 use anyhow::{Result, anyhow};
-use jiff::civil::DateTime;
+use jiff::SignedDuration;
+use jiff::civil::{Date, DateTime};
 use serde::{Deserialize, Serialize};
 
+use crate::config::Config;
+use crate::recur::Recur;
+use crate::util::now;
+
+/// Where a todo is in its workflow. `open`, `done` and `cancelled` are built in; other
+/// states come from `statuses` in the store config.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[serde(from = "String", into = "String")]
 pub enum Status {
     Open,
     Done,
+    /// Dropped without being done, e.g. won't fix or no longer needed.
+    Cancelled,
+    /// A configured state. `open` says whether todos in it count as open;
+    /// `parse_file` fills it in from the config.
+    Custom {
+        name: String,
+        open: bool,
+    },
+}
+
+impl Status {
+    pub fn name(&self) -> &str {
+        match self {
+            Status::Open => "open",
+            Status::Done => "done",
+            Status::Cancelled => "cancelled",
+            Status::Custom { name, .. } => name,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        match self {
+            Status::Open => true,
+            Status::Done | Status::Cancelled => false,
+            Status::Custom { open, .. } => *open,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl From<String> for Status {
+    fn from(name: String) -> Status {
+        match name.as_str() {
+            "open" => Status::Open,
+            "done" => Status::Done,
+            "cancelled" => Status::Cancelled,
+            _ => Status::Custom { name, open: true },
+        }
+    }
+}
+
+impl From<Status> for String {
+    fn from(status: Status) -> String {
+        match status {
+            Status::Custom { name, .. } => name,
+            builtin => builtin.name().to_string(),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Frontmatter {
     pub title: String,
     pub created: DateTime,
     pub status: Status,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub assigned: Option<String>,
+    /// Assignees, written as a plain string when there is exactly one. An
+    /// empty name is an anonymous assignment.
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "one_or_many")]
+    pub assigned: Vec<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub assigned_at: Option<DateTime>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub done_at: Option<DateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancelled_at: Option<DateTime>,
+    /// Expected effort, written in the friendly form (`2h 30m`).
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "jiff::fmt::serde::duration::friendly::compact::optional"
+    )]
+    pub estimate: Option<SignedDuration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<Date>,
+    /// Recurrence rule; completing the todo creates the next occurrence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recur: Option<Recur>,
+    /// ID of the occurrence this recurring todo was created from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Hidden from open-todo views before this date.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deferred_until: Option<Date>,
+    /// Tracked work intervals, oldest first. The last one may still be running.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub work: Vec<WorkInterval>,
+    /// Notes added with `tdo note`, oldest first. Kept out of the body so
+    /// notes added concurrently merge instead of conflicting.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<Comment>,
+    /// Append-only activity log, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<Event>,
+}
+
+/// A timestamped note on a todo.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comment {
+    pub at: DateTime,
+    /// Who added it, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub text: String,
+}
+
+impl fmt::Display for Comment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut prefix = self.at.strftime("%Y-%m-%d %H:%M").to_string();
+        if let Some(author) = &self.author {
+            prefix.push_str("  ");
+            prefix.push_str(author);
+        }
+        prefix.push_str(": ");
+        // Continuation lines are indented by the prefix's width, so they
+        // line up under the text of the first one.
+        let indent = " ".repeat(prefix.chars().count());
+        let mut lines = self.text.lines();
+        write!(f, "{prefix}{}", lines.next().unwrap_or_default())?;
+        for line in lines {
+            write!(f, "\n{indent}{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A span of time spent on a todo. `end` is unset while the timer runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkInterval {
+    pub start: DateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime>,
+}
+
+impl WorkInterval {
+    /// Length of the interval, counting a running one up to `now`.
+    pub fn duration(&self, now: DateTime) -> SignedDuration {
+        self.end.unwrap_or(now).duration_since(self.start)
+    }
+}
+
+/// One entry in a todo's activity history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    pub at: DateTime,
+    pub event: EventKind,
+    /// Extra information, e.g. the assignee for `assigned`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    Created,
+    Assigned,
+    Unassigned,
+    Done,
+    Reopened,
+    Edited,
+    Started,
+    Stopped,
+    Logged,
+    Snoozed,
+    Stale,
+    Status,
+    Cancelled,
+    Noted,
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            EventKind::Created => "created",
+            EventKind::Assigned => "assigned",
+            EventKind::Unassigned => "unassigned",
+            EventKind::Done => "done",
+            EventKind::Reopened => "reopened",
+            EventKind::Edited => "edited",
+            EventKind::Started => "started",
+            EventKind::Stopped => "stopped",
+            EventKind::Logged => "logged",
+            EventKind::Snoozed => "snoozed",
+            EventKind::Stale => "stale",
+            EventKind::Status => "status",
+            EventKind::Cancelled => "cancelled",
+            EventKind::Noted => "noted",
+        };
+        f.write_str(s)
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}  {}", self.at.strftime("%Y-%m-%d %H:%M"), self.event)?;
+        match (&self.event, self.detail.as_deref()) {
+            (EventKind::Assigned, Some(name)) if !name.is_empty() => write!(f, " to {name}"),
+            (EventKind::Unassigned, Some(name)) if !name.is_empty() => write!(f, " {name}"),
+            (EventKind::Snoozed, Some(until)) => write!(f, " until {until}"),
+            (_, Some(detail)) if !detail.is_empty() => write!(f, ": {detail}"),
+            _ => Ok(()),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -36,30 +241,148 @@ impl Todo {
     }
 
     pub fn is_open(&self) -> bool {
-        self.frontmatter.status == Status::Open
+        self.frontmatter.status.is_open()
+    }
+
+    /// Whether the todo is deferred past `today`.
+    pub fn is_snoozed(&self, today: Date) -> bool {
+        self.frontmatter.deferred_until.is_some_and(|d| d > today)
     }
 
     pub fn is_assigned(&self) -> bool {
-        self.frontmatter.assigned.is_some()
+        !self.frontmatter.assigned.is_empty()
+    }
+
+    /// Whether the todo is open and has been assigned for longer than `after`.
+    pub fn is_stale(&self, now: DateTime, after: SignedDuration) -> bool {
+        self.is_open()
+            && self.is_assigned()
+            && self
+                .frontmatter
+                .assigned_at
+                .is_some_and(|at| now.duration_since(at) > after)
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.frontmatter.tags.iter().any(|t| t == tag)
+    }
+
+    pub fn is_assigned_to(&self, name: &str) -> bool {
+        self.frontmatter.assigned.iter().any(|a| a == name)
+    }
+
+    /// Named assignees joined with ", "; empty for anonymous assignments.
+    pub fn assignees(&self) -> String {
+        let names: Vec<&str> = self
+            .frontmatter
+            .assigned
+            .iter()
+            .map(String::as_str)
+            .filter(|a| !a.is_empty())
+            .collect();
+        names.join(", ")
+    }
+
+    /// Start of the running work interval, if a timer is running.
+    pub fn running_since(&self) -> Option<DateTime> {
+        self.frontmatter
+            .work
+            .last()
+            .filter(|w| w.end.is_none())
+            .map(|w| w.start)
+    }
+
+    /// Total tracked time, counting a running timer up to `now`.
+    pub fn time_spent(&self, now: DateTime) -> SignedDuration {
+        self.frontmatter
+            .work
+            .iter()
+            .fold(SignedDuration::ZERO, |total, w| total + w.duration(now))
+    }
+
+    /// Append an event to the history, timestamped now.
+    pub fn record(&mut self, event: EventKind, detail: Option<&str>) {
+        self.frontmatter.history.push(Event {
+            at: now(),
+            event,
+            detail: detail.map(|d| d.to_string()),
+        });
+    }
+
+    /// The recorded history, starting with a synthetic `created` event for
+    /// todos written before history was tracked.
+    pub fn history(&self) -> Vec<Event> {
+        let mut events = self.frontmatter.history.clone();
+        if events.first().is_none_or(|e| e.event != EventKind::Created) {
+            events.insert(
+                0,
+                Event {
+                    at: self.frontmatter.created,
+                    event: EventKind::Created,
+                    detail: None,
+                },
+            );
+        }
+        events
     }
 
     pub fn assigned_suffix(&self) -> String {
-        match &self.frontmatter.assigned {
-            Some(name) if !name.is_empty() => format!(" (assigned: {name})"),
-            Some(_) => " (assigned)".to_string(),
-            None => String::new(),
+        match self.assignees() {
+            names if !names.is_empty() => format!(" (assigned: {names})"),
+            _ if self.is_assigned() => " (assigned)".to_string(),
+            _ => String::new(),
         }
     }
 }
 
-pub fn parse_file(raw: &str) -> Result<(Frontmatter, Option<String>)> {
+/// `assigned:` is a single string for one assignee (as in files written
+/// before multiple assignees) and a list otherwise.
+mod one_or_many {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    pub fn serialize<S: Serializer>(names: &[String], s: S) -> Result<S::Ok, S::Error> {
+        match names {
+            [one] => one.serialize(s),
+            many => many.serialize(s),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<String>, D::Error> {
+        Ok(match OneOrMany::deserialize(d)? {
+            OneOrMany::One(name) => vec![name],
+            OneOrMany::Many(names) => names,
+        })
+    }
+}
+
+/// Parse a todo file. The status must be built in or one of the
+/// `statuses` configured in `config`.
+pub fn parse_file(raw: &str, config: &Config) -> Result<(Frontmatter, Option<String>)> {
     let rest = raw
         .strip_prefix("---\n")
         .ok_or_else(|| anyhow!("missing opening ---"))?;
     let (yaml_part, after) = rest
         .split_once("\n---\n")
         .ok_or_else(|| anyhow!("missing closing ---"))?;
-    let fm: Frontmatter = serde_yml::from_str(yaml_part)?;
+    let mut fm: Frontmatter = serde_yml::from_str(yaml_part)?;
+    if let Status::Custom { name, .. } = &fm.status {
+        fm.status = config.status(name).ok_or_else(|| {
+            anyhow!(
+                "unknown status '{name}' (expected {})",
+                config.status_names().join(", ")
+            )
+        })?;
+    }
+    // `render_file` separates frontmatter and body with a blank line; drop it
+    // so that parse/render round-trips don't accumulate blank lines.
+    let after = after.strip_prefix('\n').unwrap_or(after);
     let body = if after.trim().is_empty() {
         None
     } else {
@@ -90,17 +413,72 @@ mod tests {
             title: "fix the login bug".to_string(),
             created: "2026-02-20T14:30:52".parse().unwrap(),
             status: Status::Open,
-            assigned: None,
+            assigned: vec![],
             assigned_at: None,
             done_at: None,
+            cancelled_at: None,
+            estimate: None,
+            due: None,
+            recur: None,
+            previous: None,
+            tags: vec![],
+            deferred_until: None,
+            work: vec![],
+            comments: vec![],
+            history: vec![],
         }
     }
 
+    #[test]
+    fn assigned_is_a_string_for_one_and_a_list_for_many() {
+        let mut fm = sample_frontmatter();
+        fm.assigned = vec!["alice".to_string()];
+        let rendered = render_file(&fm, None).unwrap();
+        assert!(rendered.contains("assigned: alice\n"), "{rendered}");
+        assert_eq!(
+            parse_file(&rendered, &Config::default())
+                .unwrap()
+                .0
+                .assigned,
+            fm.assigned
+        );
+
+        fm.assigned.push("bob".to_string());
+        let rendered = render_file(&fm, None).unwrap();
+        assert!(
+            rendered.contains("assigned:\n- alice\n- bob\n"),
+            "{rendered}"
+        );
+        assert_eq!(
+            parse_file(&rendered, &Config::default())
+                .unwrap()
+                .0
+                .assigned,
+            fm.assigned
+        );
+
+        fm.assigned.clear();
+        let rendered = render_file(&fm, None).unwrap();
+        assert!(!rendered.contains("assigned"), "{rendered}");
+    }
+
+    #[test]
+    fn estimate_roundtrips_in_friendly_form() {
+        let fm = Frontmatter {
+            estimate: Some(SignedDuration::from_mins(150)),
+            ..sample_frontmatter()
+        };
+        let rendered = render_file(&fm, None).unwrap();
+        assert!(rendered.contains("estimate: '2h 30m'\n"), "{rendered}");
+        let (parsed, _) = parse_file(&rendered, &Config::default()).unwrap();
+        assert_eq!(parsed.estimate, fm.estimate);
+    }
+
     #[test]
     fn render_then_parse_roundtrip_no_body() {
         let fm = sample_frontmatter();
         let rendered = render_file(&fm, None).unwrap();
-        let (parsed_fm, parsed_body) = parse_file(&rendered).unwrap();
+        let (parsed_fm, parsed_body) = parse_file(&rendered, &Config::default()).unwrap();
         assert_eq!(parsed_fm.title, fm.title);
         assert_eq!(parsed_fm.created, fm.created);
         assert_eq!(parsed_fm.status, fm.status);
@@ -112,29 +490,82 @@ mod tests {
         let fm = sample_frontmatter();
         let body = "Some notes about the bug.\nSecond line.";
         let rendered = render_file(&fm, Some(body)).unwrap();
-        let (parsed_fm, parsed_body) = parse_file(&rendered).unwrap();
+        let (parsed_fm, parsed_body) = parse_file(&rendered, &Config::default()).unwrap();
         assert_eq!(parsed_fm.title, fm.title);
         let b = parsed_body.unwrap();
         assert!(b.contains("Some notes about the bug."));
         assert!(b.contains("Second line."));
     }
 
+    #[test]
+    fn render_then_parse_preserves_body_exactly() {
+        let fm = sample_frontmatter();
+        let body = "First line.\n\nSecond paragraph.\n";
+        let rendered = render_file(&fm, Some(body)).unwrap();
+        let (_, parsed_body) = parse_file(&rendered, &Config::default()).unwrap();
+        assert_eq!(parsed_body.as_deref(), Some(body));
+    }
+
+    #[test]
+    fn history_roundtrip() {
+        let mut fm = sample_frontmatter();
+        fm.history.push(Event {
+            at: "2026-02-21T10:00:00".parse().unwrap(),
+            event: EventKind::Assigned,
+            detail: Some("alice".to_string()),
+        });
+        let rendered = render_file(&fm, None).unwrap();
+        assert!(rendered.contains("event: assigned"), "{rendered}");
+        let (parsed_fm, _) = parse_file(&rendered, &Config::default()).unwrap();
+        assert_eq!(parsed_fm.history, fm.history);
+    }
+
+    #[test]
+    fn parse_without_history() {
+        let raw = "---\ntitle: old\ncreated: 2026-02-20T14:30:52\nstatus: open\n---\n";
+        let (fm, _) = parse_file(raw, &Config::default()).unwrap();
+        assert!(fm.history.is_empty());
+        let rendered = render_file(&fm, None).unwrap();
+        assert!(!rendered.contains("history"), "{rendered}");
+    }
+
     #[test]
     fn parse_missing_opening_delimiter() {
         let raw = "title: foo\n---\n";
-        assert!(parse_file(raw).is_err());
+        assert!(parse_file(raw, &Config::default()).is_err());
     }
 
     #[test]
     fn parse_missing_closing_delimiter() {
         let raw = "---\ntitle: foo\n";
-        assert!(parse_file(raw).is_err());
+        assert!(parse_file(raw, &Config::default()).is_err());
+    }
+
+    #[test]
+    fn parse_configured_status() {
+        let config: Config =
+            serde_yml::from_str("statuses: [review, {name: wontfix, open: false}]").unwrap();
+        let raw = "---\ntitle: x\ncreated: 2026-02-20T14:30:52\nstatus: wontfix\n---\n";
+        let (fm, _) = parse_file(raw, &config).unwrap();
+        assert_eq!(fm.status.name(), "wontfix");
+        assert!(!fm.status.is_open());
+        assert!(
+            render_file(&fm, None)
+                .unwrap()
+                .contains("status: wontfix\n")
+        );
+
+        let err = parse_file(raw, &Config::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown status 'wontfix' (expected open, done, cancelled)"
+        );
     }
 
     #[test]
     fn parse_done_status() {
         let raw = "---\ntitle: done task\ncreated: 2026-02-20T14:30:52\nstatus: done\n---\n";
-        let (fm, _) = parse_file(raw).unwrap();
+        let (fm, _) = parse_file(raw, &Config::default()).unwrap();
         assert_eq!(fm.status, Status::Done);
     }
 }
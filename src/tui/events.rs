@@ -1,13 +1,19 @@
 use std::io::Stdout;
 use std::ops::ControlFlow;
+use std::time::Duration;
 
 use anyhow::Result;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 use ratatui::backend::CrosstermBackend;
 use ratatui::{Terminal, TerminalOptions, Viewport};
 
-use super::{App, Mode};
+use super::{App, Column, Mode};
 use crate::ops;
+use crate::template;
+use crate::todo::Status;
+
+/// How often the screen is redrawn while a timer runs.
+const REDRAW_INTERVAL: Duration = Duration::from_secs(1);
 
 pub fn run_event_loop(
     mut terminal: Terminal<CrosstermBackend<Stdout>>,
@@ -16,6 +22,11 @@ pub fn run_event_loop(
     let mut current_height = app.viewport_height();
     loop {
         terminal.draw(|f| super::ui::draw(f, &mut *app))?;
+        // While a timer runs, wake up periodically so the header stays
+        // current; otherwise block until the next event.
+        if ops::running_todo(&app.store).is_some() && !event::poll(REDRAW_INTERVAL)? {
+            continue;
+        }
         match event::read()? {
             Event::Key(key) => match handle_key(&mut terminal, app, key)? {
                 ControlFlow::Break(()) => return Ok(terminal),
@@ -76,6 +87,11 @@ fn handle_key(
     match &app.mode {
         Mode::Normal => handle_normal(terminal, app, key),
         Mode::ConfirmDelete { .. } => handle_confirm_delete(app, key),
+        Mode::Snooze { .. } => handle_snooze(app, key),
+        Mode::Note { .. } => handle_note(app, key),
+        Mode::Template { .. } => handle_template(app, key),
+        Mode::Board { .. } => handle_board(app, key),
+        Mode::Pick { .. } => handle_pick(app, key),
     }
 }
 
@@ -110,15 +126,62 @@ fn handle_normal(
             KeyCode::Char('s') => {
                 if let Some(todo) = app.selected_todo() {
                     let id = todo.id.clone();
-                    let is_assigned = todo.is_assigned();
-                    if is_assigned {
-                        ops::unassign_todo(&mut app.store, &id)?;
-                    } else {
-                        ops::assign_todo(&mut app.store, &id, None)?;
+                    // Toggle the current user's assignment. Without an
+                    // identity, toggle an anonymous assignment as before.
+                    match app.store.current_user() {
+                        Some(me) if todo.is_assigned_to(&me) => {
+                            ops::unassign_todo(&mut app.store, &id, &[me])?;
+                        }
+                        None if todo.is_assigned() => {
+                            ops::unassign_todo(&mut app.store, &id, &[])?;
+                        }
+                        me => {
+                            let me = me.unwrap_or_default();
+                            ops::assign_todo(&mut app.store, &id, &[me])?;
+                        }
                     }
                     return Ok(ControlFlow::Continue(true));
                 }
             }
+            KeyCode::Char('b') => {
+                app.mode = Mode::Board {
+                    column: Column::Open,
+                    row: 0,
+                };
+            }
+            KeyCode::Char('t') => {
+                // Cycle through the open workflow states.
+                if let Some(todo) = app.selected_todo()
+                    && todo.is_open()
+                {
+                    let id = todo.id.clone();
+                    let next = app.store.config.next_open_status(&todo.frontmatter.status);
+                    ops::set_status(&mut app.store, &id, next.name())?;
+                    return Ok(ControlFlow::Continue(true));
+                }
+            }
+            KeyCode::Char('z') => {
+                if let Some(todo) = app.selected_todo()
+                    && todo.is_open()
+                {
+                    app.mode = Mode::Snooze {
+                        id: todo.id.clone(),
+                        title: todo.title().to_string(),
+                        input: String::new(),
+                        error: None,
+                    };
+                }
+            }
+            KeyCode::Char('n') => {
+                if let Some(todo) = app.selected_todo() {
+                    app.mode = Mode::Note {
+                        id: todo.id.clone(),
+                        title: todo.title().to_string(),
+                        input: String::new(),
+                        error: None,
+                    };
+                }
+            }
             KeyCode::Char('x') => {
                 if let Some(todo) = app.selected_todo() {
                     let id = todo.id.clone();
@@ -143,7 +206,27 @@ fn handle_normal(
         }
         KeyCode::Enter => {
             if app.is_on_create_new() {
-                ops::create_todo(&mut app.store, &app.input.clone(), None)?;
+                let names = template::names(app.store.dir())?;
+                if !names.is_empty() {
+                    // Ask for a template, starting on the configured default.
+                    let templates: Vec<Option<String>> = std::iter::once(None)
+                        .chain(names.into_iter().map(Some))
+                        .collect();
+                    let default = app.store.config.default_template.clone();
+                    let selected = templates.iter().position(|t| *t == default).unwrap_or(0);
+                    app.mode = Mode::Template {
+                        title: app.input.clone(),
+                        templates,
+                        selected,
+                    };
+                    return Ok(ControlFlow::Continue(false));
+                }
+                ops::create_todo(
+                    &mut app.store,
+                    &app.input.clone(),
+                    None,
+                    &ops::CreateOptions::default(),
+                )?;
                 app.input.clear();
                 app.refilter();
                 return Ok(ControlFlow::Continue(true));
@@ -197,7 +280,9 @@ fn handle_confirm_delete(app: &mut App, key: KeyEvent) -> Result<ControlFlow<(),
 
     match key.code {
         KeyCode::Char('y') | KeyCode::Enter => {
-            app.store.delete(&id)?;
+            let todo = app.store.delete(&id)?;
+            app.store
+                .commit(&format!("tdo: delete {} {}", todo.id, todo.title()));
             app.mode = Mode::Normal;
             return Ok(ControlFlow::Continue(true));
         }
@@ -208,3 +293,242 @@ fn handle_confirm_delete(app: &mut App, key: KeyEvent) -> Result<ControlFlow<(),
     }
     Ok(ControlFlow::Continue(false))
 }
+
+fn handle_snooze(app: &mut App, key: KeyEvent) -> Result<ControlFlow<(), bool>> {
+    let Mode::Snooze {
+        ref id,
+        ref mut input,
+        ref mut error,
+        ..
+    } = app.mode
+    else {
+        return Ok(ControlFlow::Continue(false));
+    };
+
+    match key.code {
+        KeyCode::Char(c) => {
+            input.push(c);
+            *error = None;
+        }
+        KeyCode::Backspace => {
+            input.pop();
+            *error = None;
+        }
+        KeyCode::Enter => {
+            let (id, until) = (id.clone(), input.clone());
+            // A bad date keeps the prompt open with the error shown.
+            if let Err(e) = ops::snooze_todo(&mut app.store, &id, Some(&until)) {
+                *error = Some(e.to_string());
+                return Ok(ControlFlow::Continue(false));
+            }
+            app.mode = Mode::Normal;
+            return Ok(ControlFlow::Continue(true));
+        }
+        KeyCode::Esc => {
+            app.mode = Mode::Normal;
+        }
+        _ => {}
+    }
+    Ok(ControlFlow::Continue(false))
+}
+
+fn handle_template(app: &mut App, key: KeyEvent) -> Result<ControlFlow<(), bool>> {
+    let Mode::Template {
+        ref title,
+        ref templates,
+        ref mut selected,
+    } = app.mode
+    else {
+        return Ok(ControlFlow::Continue(false));
+    };
+
+    match key.code {
+        KeyCode::Left | KeyCode::BackTab => {
+            *selected = (*selected + templates.len() - 1) % templates.len();
+        }
+        KeyCode::Right | KeyCode::Tab => *selected = (*selected + 1) % templates.len(),
+        KeyCode::Enter => {
+            let title = title.clone();
+            let options = ops::CreateOptions {
+                template: templates[*selected].clone(),
+                ..Default::default()
+            };
+            ops::create_todo(&mut app.store, &title, None, &options)?;
+            app.mode = Mode::Normal;
+            app.input.clear();
+            app.refilter();
+            return Ok(ControlFlow::Continue(true));
+        }
+        KeyCode::Esc => app.mode = Mode::Normal,
+        _ => {}
+    }
+    Ok(ControlFlow::Continue(false))
+}
+
+fn handle_note(app: &mut App, key: KeyEvent) -> Result<ControlFlow<(), bool>> {
+    let Mode::Note {
+        ref id,
+        ref mut input,
+        ref mut error,
+        ..
+    } = app.mode
+    else {
+        return Ok(ControlFlow::Continue(false));
+    };
+
+    match key.code {
+        KeyCode::Char(c) => {
+            input.push(c);
+            *error = None;
+        }
+        KeyCode::Backspace => {
+            input.pop();
+            *error = None;
+        }
+        KeyCode::Enter => {
+            let (id, text) = (id.clone(), input.clone());
+            if let Err(e) = ops::add_comment(&mut app.store, &id, &text, None) {
+                *error = Some(e.to_string());
+                return Ok(ControlFlow::Continue(false));
+            }
+            app.mode = Mode::Normal;
+            return Ok(ControlFlow::Continue(true));
+        }
+        KeyCode::Esc => {
+            app.mode = Mode::Normal;
+        }
+        _ => {}
+    }
+    Ok(ControlFlow::Continue(false))
+}
+
+fn handle_pick(app: &mut App, key: KeyEvent) -> Result<ControlFlow<(), bool>> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Char('c') if ctrl => return Ok(ControlFlow::Break(())),
+        KeyCode::Char('a') if ctrl => {
+            app.show_all = !app.show_all;
+            app.reload();
+        }
+        KeyCode::Char(c) if !ctrl => {
+            app.input.push(c);
+            app.refilter();
+        }
+        KeyCode::Backspace => {
+            app.input.pop();
+            app.refilter();
+        }
+        KeyCode::Enter => {
+            let id = app.selected_todo().map(|t| t.id.clone());
+            if let Mode::Pick { picked, .. } = &mut app.mode
+                && id.is_some()
+            {
+                *picked = id;
+                return Ok(ControlFlow::Break(()));
+            }
+        }
+        KeyCode::Esc => {
+            if app.input.is_empty() {
+                return Ok(ControlFlow::Break(()));
+            }
+            app.input.clear();
+            app.refilter();
+        }
+        KeyCode::Down => app.cursor_down(),
+        KeyCode::Up => app.cursor_up(),
+        _ => {}
+    }
+    Ok(ControlFlow::Continue(false))
+}
+
+fn handle_board(app: &mut App, key: KeyEvent) -> Result<ControlFlow<(), bool>> {
+    let Mode::Board { column, row } = app.mode else {
+        return Ok(ControlFlow::Continue(false));
+    };
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+    let len = app.board_column(column).len();
+
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Char('c') if ctrl => return Ok(ControlFlow::Break(())),
+        KeyCode::Char('b') if ctrl => app.mode = Mode::Normal,
+        KeyCode::Esc => app.mode = Mode::Normal,
+        KeyCode::Left if shift => return move_card(app, column, row, column.left()),
+        KeyCode::Right if shift => return move_card(app, column, row, column.right()),
+        KeyCode::Char('<') | KeyCode::Char('H') => {
+            return move_card(app, column, row, column.left());
+        }
+        KeyCode::Char('>') | KeyCode::Char('L') => {
+            return move_card(app, column, row, column.right());
+        }
+        KeyCode::Left | KeyCode::Char('h') => {
+            if let Some(left) = column.left() {
+                select_card(app, left, row);
+            }
+        }
+        KeyCode::Right | KeyCode::Char('l') => {
+            if let Some(right) = column.right() {
+                select_card(app, right, row);
+            }
+        }
+        KeyCode::Up | KeyCode::Char('k') => select_card(app, column, row.saturating_sub(1)),
+        KeyCode::Down | KeyCode::Char('j') if row + 1 < len => select_card(app, column, row + 1),
+        _ => {}
+    }
+    Ok(ControlFlow::Continue(false))
+}
+
+/// Select `row` in `column`, clamped to the cards it has.
+fn select_card(app: &mut App, column: Column, row: usize) {
+    let len = app.board_column(column).len();
+    app.mode = Mode::Board {
+        column,
+        row: row.min(len.saturating_sub(1)),
+    };
+}
+
+/// Move the selected card to the adjacent column `to`: assigning it to you
+/// (or releasing it), marking it done, or reopening it. The selection
+/// follows the card.
+fn move_card(
+    app: &mut App,
+    from: Column,
+    row: usize,
+    to: Option<Column>,
+) -> Result<ControlFlow<(), bool>> {
+    let (Some(to), Some(todo)) = (to, app.board_column(from).get(row).copied()) else {
+        return Ok(ControlFlow::Continue(false));
+    };
+    let id = todo.id.clone();
+    let custom_status = todo.frontmatter.status != Status::Open;
+    let me = app.store.current_user().unwrap_or_default();
+    match (from, to) {
+        (Column::Open, Column::InProgress) => {
+            ops::assign_todo(&mut app.store, &id, &[me])?;
+        }
+        (Column::InProgress, Column::Open) => {
+            ops::unassign_todo(&mut app.store, &id, &[])?;
+            if custom_status {
+                ops::set_status(&mut app.store, &id, Status::Open.name())?;
+            }
+        }
+        (Column::InProgress, Column::Done) => {
+            ops::mark_done(&mut app.store, &id)?;
+        }
+        (Column::Done, Column::InProgress) => {
+            let todo = ops::reopen_todo(&mut app.store, &id)?;
+            // A reopened card without assignees would land in Open.
+            if !todo.is_assigned() {
+                ops::assign_todo(&mut app.store, &id, &[me])?;
+            }
+        }
+        _ => return Ok(ControlFlow::Continue(false)),
+    }
+    let row = app
+        .board_column(to)
+        .iter()
+        .position(|t| t.id == id)
+        .unwrap_or(0);
+    app.mode = Mode::Board { column: to, row };
+    Ok(ControlFlow::Continue(true))
+}
@@ -7,11 +7,16 @@ use ratatui::backend::CrosstermBackend;
 use ratatui::widgets::ListState;
 use ratatui::{Terminal, TerminalOptions, Viewport};
 
+use crate::filter::Filter;
 use crate::storage::Store;
-use crate::todo::Todo;
+use crate::todo::{Status, Todo};
+use crate::util::now;
 
 const MAX_HEIGHT: u16 = 20;
 
+/// How long done todos stay in the board's done column.
+const BOARD_DONE_HOURS: i64 = 7 * 24;
+
 /// Returns the number of rows in the terminal, or `MAX_HEIGHT` if the query fails.
 fn terminal_rows() -> u16 {
     terminal::size().map(|(_, rows)| rows).unwrap_or(MAX_HEIGHT)
@@ -31,13 +36,87 @@ pub struct App {
 
 pub enum Mode {
     Normal,
-    ConfirmDelete { id: String, title: String },
+    ConfirmDelete {
+        id: String,
+        title: String,
+    },
+    /// Prompting for the date to snooze the selected todo until.
+    Snooze {
+        id: String,
+        title: String,
+        input: String,
+        error: Option<String>,
+    },
+    /// Choosing a body template for a new todo titled `title`. `selected`
+    /// indexes `templates`, where `None` is no template.
+    Template {
+        title: String,
+        templates: Vec<Option<String>>,
+        selected: usize,
+    },
+    /// Prompting for a note to add to the selected todo.
+    Note {
+        id: String,
+        title: String,
+        input: String,
+        error: Option<String>,
+    },
+    /// Choosing the todo for a CLI command that was run without one
+    /// (`pick_todo`). There is no create line, and Enter ends the TUI.
+    Pick {
+        /// What the todo is picked for, e.g. "mark done".
+        action: String,
+        picked: Option<String>,
+    },
+    /// Kanban board: the selected card is `row` in `column`.
+    Board {
+        column: Column,
+        row: usize,
+    },
+}
+
+/// Columns of the board view, left to right.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    /// Open and unassigned.
+    Open,
+    /// Assigned, or in a configured open state other than `open`.
+    InProgress,
+    /// Done within the last week.
+    Done,
+}
+
+impl Column {
+    pub const ALL: [Column; 3] = [Column::Open, Column::InProgress, Column::Done];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Column::Open => "Open",
+            Column::InProgress => "In progress",
+            Column::Done => "Done",
+        }
+    }
+
+    pub fn left(self) -> Option<Column> {
+        match self {
+            Column::Open => None,
+            Column::InProgress => Some(Column::Open),
+            Column::Done => Some(Column::InProgress),
+        }
+    }
+
+    pub fn right(self) -> Option<Column> {
+        match self {
+            Column::Open => Some(Column::InProgress),
+            Column::InProgress => Some(Column::Done),
+            Column::Done => None,
+        }
+    }
 }
 
 impl App {
     pub fn new(store: Store) -> Self {
-        let mut todos: Vec<Todo> = store.list_open().into_iter().cloned().collect();
-        todos.sort_by_key(|t| t.is_assigned());
+        let todos = list_todos(&store, false);
         let filtered: Vec<usize> = (0..todos.len()).collect();
         let mut list_state = ListState::default();
         if !filtered.is_empty() {
@@ -54,9 +133,10 @@ impl App {
         }
     }
 
-    /// Whether the "Create new" line is shown (input is non-empty).
+    /// Whether the "Create new" line is shown (input is non-empty, and not
+    /// picking a todo).
     pub fn has_create_line(&self) -> bool {
-        !self.input.is_empty()
+        !self.input.is_empty() && !matches!(self.mode, Mode::Pick { .. })
     }
 
     /// The index in the selectable list where filtered todos start.
@@ -145,42 +225,95 @@ impl App {
     /// Re-read the store and recompute filtered list, clamping (not
     /// resetting) the selection so arrow-key position is preserved.
     pub fn reload(&mut self) {
-        self.todos = if self.show_all {
-            self.store.list_all().to_vec()
-        } else {
-            self.store.list_open().into_iter().cloned().collect()
-        };
-        self.todos.sort_by_key(|t| t.is_assigned());
+        self.todos = list_todos(&self.store, self.show_all);
         self.compute_filtered();
         self.clamp_selection();
     }
 
     pub fn cursor_down(&mut self) {
         let total = self.selectable_count();
-        if let Some(sel) = self.list_state.selected() {
-            if sel + 1 < total {
-                self.list_state.select(Some(sel + 1));
-            }
+        if let Some(sel) = self.list_state.selected()
+            && sel + 1 < total
+        {
+            self.list_state.select(Some(sel + 1));
         }
     }
 
     pub fn cursor_up(&mut self) {
-        if let Some(sel) = self.list_state.selected() {
-            if sel > 0 {
-                self.list_state.select(Some(sel - 1));
+        if let Some(sel) = self.list_state.selected()
+            && sel > 0
+        {
+            self.list_state.select(Some(sel - 1));
+        }
+    }
+
+    /// The cards in a board column. Snoozed todos are left out, done ones
+    /// are most recent first.
+    pub fn board_column(&self, column: Column) -> Vec<&Todo> {
+        match column {
+            Column::Open => self
+                .store
+                .list_open()
+                .into_iter()
+                .filter(|t| t.frontmatter.status == Status::Open && !t.is_assigned())
+                .collect(),
+            Column::InProgress => self
+                .store
+                .list_open()
+                .into_iter()
+                .filter(|t| t.frontmatter.status != Status::Open || t.is_assigned())
+                .collect(),
+            Column::Done => {
+                let since = now() - jiff::SignedDuration::from_hours(BOARD_DONE_HOURS);
+                let mut done: Vec<&Todo> = self
+                    .store
+                    .list_all()
+                    .iter()
+                    .filter(|t| t.frontmatter.status == Status::Done)
+                    .filter(|t| t.frontmatter.done_at.is_some_and(|at| at >= since))
+                    .collect();
+                done.sort_by_key(|t| std::cmp::Reverse(t.frontmatter.done_at));
+                done
             }
         }
     }
 
     /// Viewport height: (input + items) + 2 (border) + 1 (help line), capped at
     /// `MAX_HEIGHT` and the terminal height minus one row of margin.
+    /// In board mode the items are the cards of the longest column.
     pub fn viewport_height(&self) -> u16 {
-        let content_lines = (1 + self.selectable_count()).min(u16::MAX as usize) as u16;
+        let content_lines = if let Mode::Board { .. } = self.mode {
+            Column::ALL
+                .iter()
+                .map(|&c| self.board_column(c).len())
+                .max()
+                .unwrap_or(0)
+                .max(1)
+        } else {
+            1 + self.selectable_count()
+        };
+        let content_lines = content_lines.min(u16::MAX as usize) as u16;
         let cap = MAX_HEIGHT.min(terminal_rows().saturating_sub(1));
         content_lines.saturating_add(3).min(cap)
     }
 }
 
+/// The todos the list shows: open and unsnoozed ones (everything with
+/// `all`), unassigned first.
+fn list_todos(store: &Store, all: bool) -> Vec<Todo> {
+    let filter = Filter {
+        all,
+        ..Filter::default()
+    };
+    let mut todos: Vec<Todo> = filter
+        .apply(store.list_all(), now().date())
+        .into_iter()
+        .cloned()
+        .collect();
+    todos.sort_by_key(|t| t.is_assigned());
+    todos
+}
+
 /// Fuzzy subsequence match (case-insensitive).
 fn fuzzy_match(query: &str, text: &str) -> bool {
     let mut text_chars = text.chars().flat_map(|c| c.to_lowercase());
@@ -206,13 +339,40 @@ impl Drop for RawModeGuard {
 }
 
 pub fn run_tui(store: Store) -> Result<()> {
+    let mut app = App::new(store);
+    run_inline(&mut app, false)
+}
+
+/// Let the user pick a todo for a command run without one, with the TUI's
+/// list and filter. `all` starts with done todos shown, for `reopen`.
+/// Returns the store back along with the picked ID, `None` if cancelled.
+pub fn pick_todo(store: Store, action: &str, all: bool) -> Result<(Store, Option<String>)> {
+    let mut app = App::new(store);
+    app.mode = Mode::Pick {
+        action: action.to_string(),
+        picked: None,
+    };
+    if all {
+        app.show_all = true;
+        app.reload();
+    }
+    run_inline(&mut app, true)?;
+    let picked = match app.mode {
+        Mode::Pick { picked, .. } => picked,
+        _ => None,
+    };
+    Ok((app.store, picked))
+}
+
+/// Run `app` in an inline viewport below the cursor until it quits. With
+/// `clear`, the viewport is erased afterwards instead of left on screen.
+fn run_inline(app: &mut App, clear: bool) -> Result<()> {
     terminal::enable_raw_mode()?;
     let _raw_guard = RawModeGuard;
 
     let stdout = std::io::stdout();
     let backend = CrosstermBackend::new(stdout);
 
-    let mut app = App::new(store);
     let height = app.viewport_height();
 
     let terminal = Terminal::with_options(
@@ -221,12 +381,21 @@ pub fn run_tui(store: Store) -> Result<()> {
             viewport: Viewport::Inline(height),
         },
     )?;
-    let result = events::run_event_loop(terminal, &mut app);
+    let result = events::run_event_loop(terminal, app);
 
     // Disable explicitly so cursor positioning works in cooked mode.
     // The guard will no-op on drop since raw mode is already off.
     terminal::disable_raw_mode()?;
     match result {
+        Ok(mut terminal) if clear => {
+            let viewport = terminal.get_frame().area();
+            crossterm::execute!(
+                std::io::stdout(),
+                crossterm::cursor::MoveTo(0, viewport.y),
+                terminal::Clear(terminal::ClearType::FromCursorDown)
+            )?;
+            Ok(())
+        }
         Ok(mut terminal) => {
             let viewport = terminal.get_frame().area();
             crossterm::execute!(
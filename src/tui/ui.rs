@@ -2,9 +2,12 @@ use ratatui::Frame;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
 
-use super::{App, Mode};
+use super::{App, Column, Mode};
+use crate::config::StatusColor;
+use crate::todo::Status;
+use crate::util::{format_duration, now};
 
 /// Prefix shown before the input text in the search field.
 const INPUT_PREFIX: &str = "> ";
@@ -15,15 +18,72 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         .constraints([Constraint::Min(1), Constraint::Length(1)])
         .split(f.area());
 
+    if let Mode::Board { column, row } = app.mode {
+        draw_board(f, app, column, row, chunks[0]);
+        draw_help(
+            f,
+            "\u{2190}\u{2192}:column  \u{2191}\u{2193}:card  <>:move card  Esc:list  ^Q:quit",
+            chunks[1],
+        );
+        return;
+    }
+
     draw_list(f, app, chunks[0]);
 
     match &app.mode {
-        Mode::Normal => {
-            draw_help(
+        Mode::Board { .. } => {}
+        Mode::Pick { action, .. } => draw_help(
+            f,
+            &format!("Choose a todo to {action}  Enter:choose  ^A:all  Esc:cancel"),
+            chunks[1],
+        ),
+        Mode::Template {
+            title,
+            templates,
+            selected,
+        } => {
+            let dim = Style::default().fg(Color::DarkGray);
+            let mut spans = vec![Span::styled(format!("Template for '{title}': "), dim)];
+            for (i, template) in templates.iter().enumerate() {
+                let name = template.as_deref().unwrap_or("none");
+                let style = if i == *selected {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    dim
+                };
+                spans.push(Span::styled(name.to_string(), style));
+                spans.push(Span::raw(" "));
+            }
+            spans.push(Span::styled(
+                " \u{2190}\u{2192}:choose  Enter:create  Esc:cancel",
+                dim,
+            ));
+            f.render_widget(Paragraph::new(Line::from(spans)), chunks[1]);
+        }
+        Mode::Note {
+            title,
+            input,
+            error,
+            ..
+        } => match error {
+            Some(error) => {
+                let help = Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red));
+                f.render_widget(help, chunks[1]);
+            }
+            None => draw_help(
                 f,
-                "Enter:select  ^D:done  ^S:assign  ^X:delete  ^A:all  ^Q:quit",
+                &format!("Note on '{title}': {input}  Enter:add  Esc:cancel"),
                 chunks[1],
-            );
+            ),
+        },
+        Mode::Normal => {
+            // ^T only does something once extra open states are configured.
+            let help = if app.store.config.statuses.iter().any(|s| s.open) {
+                "Enter:select  ^D:done  ^T:status  ^S:assign  ^Z:snooze  ^N:note  ^X:delete  ^A:all  ^B:board  ^Q:quit"
+            } else {
+                "Enter:select  ^D:done  ^S:assign  ^Z:snooze  ^N:note  ^X:delete  ^A:all  ^B:board  ^Q:quit"
+            };
+            draw_help(f, help, chunks[1]);
         }
         Mode::ConfirmDelete { title, .. } => {
             draw_help(
@@ -32,6 +92,24 @@ pub fn draw(f: &mut Frame, app: &mut App) {
                 chunks[1],
             );
         }
+        Mode::Snooze {
+            title,
+            input,
+            error,
+            ..
+        } => match error {
+            Some(error) => {
+                let help = Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red));
+                f.render_widget(help, chunks[1]);
+            }
+            None => draw_help(
+                f,
+                &format!(
+                    "Snooze '{title}' until (3d, monday, 2026-12-01): {input}  Enter:confirm  Esc:cancel"
+                ),
+                chunks[1],
+            ),
+        },
     }
 }
 
@@ -47,7 +125,19 @@ fn draw_list(f: &mut Frame, app: &mut App, area: Rect) {
 
     let all_todos = app.store.list_all();
     let open_count = all_todos.iter().filter(|t| t.is_open()).count();
-    let done_count = all_todos.len() - open_count;
+    let cancelled_count = all_todos
+        .iter()
+        .filter(|t| t.frontmatter.status == Status::Cancelled)
+        .count();
+    let done_count = all_todos
+        .iter()
+        .filter(|t| t.frontmatter.status == Status::Done)
+        .count();
+    let today = now().date();
+    let snoozed_count = all_todos
+        .iter()
+        .filter(|t| t.is_open() && t.is_snoozed(today))
+        .count();
 
     let mut title_spans = vec![
         Span::raw(" tdo  "),
@@ -61,6 +151,20 @@ fn draw_list(f: &mut Frame, app: &mut App, area: Rect) {
             Style::default().fg(Color::DarkGray),
         ),
     ];
+    if cancelled_count > 0 {
+        title_spans.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
+        title_spans.push(Span::styled(
+            format!("{cancelled_count} cancelled"),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    if snoozed_count > 0 {
+        title_spans.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
+        title_spans.push(Span::styled(
+            format!("{snoozed_count} snoozed"),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
     let skipped = app.store.skipped;
     if skipped > 0 {
         title_spans.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
@@ -69,6 +173,28 @@ fn draw_list(f: &mut Frame, app: &mut App, area: Rect) {
             Style::default().fg(Color::Red),
         ));
     }
+    let duplicates = app.store.duplicates.len();
+    if duplicates > 0 {
+        title_spans.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
+        title_spans.push(Span::styled(
+            format!(
+                "{duplicates} duplicate id{}",
+                if duplicates == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    if let Some(running) = all_todos.iter().find(|t| t.running_since().is_some()) {
+        title_spans.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
+        title_spans.push(Span::styled(
+            format!(
+                "\u{23f1} {} {}",
+                running.id,
+                format_duration(running.time_spent(now()))
+            ),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
     title_spans.push(Span::raw(" "));
     let title = Line::from(title_spans);
 
@@ -95,9 +221,13 @@ fn draw_list(f: &mut Frame, app: &mut App, area: Rect) {
 
     // Render the search/input field (always visible at top)
     let input_line = if app.input.is_empty() {
+        let placeholder = match app.mode {
+            Mode::Pick { .. } => "Search...",
+            _ => "Search or create...",
+        };
         Line::from(vec![
             Span::raw(INPUT_PREFIX),
-            Span::styled("Search or create...", Style::default().fg(Color::DarkGray)),
+            Span::styled(placeholder, Style::default().fg(Color::DarkGray)),
         ])
     } else {
         Line::from(format!("{INPUT_PREFIX}{}", app.input))
@@ -117,13 +247,43 @@ fn draw_list(f: &mut Frame, app: &mut App, area: Rect) {
     for &idx in &app.filtered {
         let todo = &app.todos[idx];
         let mut spans = Vec::new();
-        if !todo.is_open() {
+        let status = &todo.frontmatter.status;
+        if *status == Status::Cancelled {
             spans.push(Span::styled(
-                format!("{}  [done] {}", todo.id, todo.title()),
+                format!("{}  [cancelled] ", todo.id),
+                Style::default().fg(Color::DarkGray),
+            ));
+            spans.push(Span::styled(
+                todo.title().to_string(),
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::CROSSED_OUT),
+            ));
+        } else if !todo.is_open() {
+            spans.push(Span::styled(
+                format!("{}  [{status}] {}", todo.id, todo.title()),
                 Style::default().fg(Color::DarkGray),
             ));
         } else {
-            spans.push(Span::raw(format!("{}  {}", todo.id, todo.title())));
+            spans.push(Span::raw(format!("{}  ", todo.id)));
+            if *status != Status::Open {
+                let style = app
+                    .store
+                    .config
+                    .status_color(status)
+                    .map_or_else(Style::default, |c| Style::default().fg(status_color(c)));
+                spans.push(Span::styled(format!("[{status}] "), style));
+            }
+            spans.push(Span::raw(todo.title().to_string()));
+        }
+        if let Some(until) = todo.frontmatter.deferred_until
+            && todo.is_open()
+            && todo.is_snoozed(today)
+        {
+            spans.push(Span::styled(
+                format!(" (snoozed until {until})"),
+                Style::default().fg(Color::DarkGray),
+            ));
         }
         if todo.is_assigned() {
             spans.push(Span::styled(
@@ -143,6 +303,74 @@ fn draw_list(f: &mut Frame, app: &mut App, area: Rect) {
     f.set_cursor_position((cursor_x, cursor_y));
 }
 
+fn draw_board(f: &mut Frame, app: &App, selected: Column, row: usize, area: Rect) {
+    let areas = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 3); 3])
+        .split(area);
+
+    for (&column, &area) in Column::ALL.iter().zip(areas.iter()) {
+        let cards = app.board_column(column);
+        let border = if column == selected {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border)
+            .title(format!(" {} ({}) ", column.title(), cards.len()));
+
+        let items: Vec<ListItem> = cards
+            .iter()
+            .map(|todo| {
+                let mut spans = vec![Span::styled(
+                    format!("{} ", todo.id),
+                    Style::default().fg(Color::DarkGray),
+                )];
+                let status = &todo.frontmatter.status;
+                if todo.is_open() && *status != Status::Open {
+                    let style = app
+                        .store
+                        .config
+                        .status_color(status)
+                        .map_or_else(Style::default, |c| Style::default().fg(status_color(c)));
+                    spans.push(Span::styled(format!("[{status}] "), style));
+                }
+                spans.push(Span::raw(todo.title().to_string()));
+                if todo.is_assigned() {
+                    spans.push(Span::styled(
+                        todo.assigned_suffix(),
+                        Style::default().fg(Color::Magenta),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let mut state = ListState::default();
+        if column == selected && !cards.is_empty() {
+            state.select(Some(row.min(cards.len() - 1)));
+        }
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(list, area, &mut state);
+    }
+}
+
+fn status_color(color: StatusColor) -> Color {
+    match color {
+        StatusColor::Red => Color::Red,
+        StatusColor::Green => Color::Green,
+        StatusColor::Yellow => Color::Yellow,
+        StatusColor::Blue => Color::Blue,
+        StatusColor::Magenta => Color::Magenta,
+        StatusColor::Cyan => Color::Cyan,
+        StatusColor::Gray => Color::DarkGray,
+    }
+}
+
 fn draw_help(f: &mut Frame, text: &str, area: Rect) {
     let help = Paragraph::new(text).style(Style::default().fg(Color::DarkGray));
     f.render_widget(help, area);
@@ -1,5 +1,7 @@
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
 use is_terminal::IsTerminal;
+use jiff::civil::{Date, DateTime, Weekday};
+use jiff::{SignedDuration, Span, SpanRelativeTo};
 use rand::RngExt;
 
 /// Generate a random 4-hex-character ID, retrying if `is_taken` returns true.
@@ -40,14 +42,117 @@ pub fn slugify(title: &str) -> String {
     truncated.to_string()
 }
 
+/// The current local time, as stored in todo timestamps.
+pub fn now() -> DateTime {
+    DateTime::from(jiff::Zoned::now())
+}
+
+/// Parse a human duration like `45m`, `1h30m` or `2d` (days are 24 hours).
+pub fn parse_duration(s: &str) -> Result<SignedDuration> {
+    let span: Span = s
+        .trim()
+        .parse()
+        .with_context(|| format!("invalid duration '{s}' (expected e.g. 45m, 1h30m, 2d)"))?;
+    Ok(span.to_duration(SpanRelativeTo::days_are_24_hours())?)
+}
+
+/// Format a duration as hours and minutes, e.g. `1h 30m`. Seconds are dropped.
+pub fn format_duration(d: SignedDuration) -> String {
+    let minutes = SignedDuration::from_mins(d.as_mins());
+    if minutes.is_zero() {
+        "0m".to_string()
+    } else {
+        format!("{minutes:#}")
+    }
+}
+
+/// Parse a point in the past: a date (`2026-02-01`, meaning its start), a
+/// datetime, or a duration ago (`7d`).
+pub fn parse_since(s: &str) -> Result<DateTime> {
+    if let Ok(date) = s.parse::<Date>() {
+        return Ok(date.to_datetime(jiff::civil::Time::midnight()));
+    }
+    if let Ok(dt) = s.parse::<DateTime>() {
+        return Ok(dt);
+    }
+    let ago = parse_duration(s).map_err(|_| {
+        anyhow!("invalid time '{s}' (expected a date like 2026-02-01 or a duration like 7d)")
+    })?;
+    Ok(now().saturating_sub(ago))
+}
+
+/// Parse a calendar date relative to `today`: `2026-03-01`, `today`,
+/// `tomorrow`, a weekday (`monday`/`mon`, the next one after today), or a
+/// duration ahead (`3d`, `2w`).
+pub fn parse_date(s: &str, today: Date) -> Result<Date> {
+    let lower = s.trim().to_lowercase();
+    match lower.as_str() {
+        "today" => return Ok(today),
+        "tomorrow" => return Ok(today.saturating_add(jiff::ToSpan::days(1))),
+        _ => {}
+    }
+    if let Some(weekday) = parse_weekday(&lower) {
+        return Ok(today.nth_weekday(1, weekday)?);
+    }
+    if let Ok(date) = lower.parse::<Date>() {
+        return Ok(date);
+    }
+    let ahead = parse_duration(s).map_err(|_| {
+        anyhow!(
+            "invalid date '{s}' (expected a date like 2026-03-01, tomorrow, monday, or a duration like 3d)"
+        )
+    })?;
+    Ok(today.saturating_add(jiff::ToSpan::days(ahead.as_hours() / 24)))
+}
+
+fn parse_weekday(s: &str) -> Option<Weekday> {
+    let weekday = match s {
+        "monday" | "mon" => Weekday::Monday,
+        "tuesday" | "tue" => Weekday::Tuesday,
+        "wednesday" | "wed" => Weekday::Wednesday,
+        "thursday" | "thu" => Weekday::Thursday,
+        "friday" | "fri" => Weekday::Friday,
+        "saturday" | "sat" => Weekday::Saturday,
+        "sunday" | "sun" => Weekday::Sunday,
+        _ => return None,
+    };
+    Some(weekday)
+}
+
 pub fn stdout_is_tty() -> bool {
     std::io::stdout().is_terminal()
 }
 
+/// Whether to color output: stdout is a terminal and `NO_COLOR` isn't set
+/// (see <https://no-color.org>).
+pub fn use_color() -> bool {
+    stdout_is_tty() && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+}
+
+/// Whether the user can answer a prompt: stdin and stderr are terminals.
+pub fn can_prompt() -> bool {
+    std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn parse_date_forms() {
+        let today: Date = "2026-02-27".parse().unwrap();
+        let parse = |s| parse_date(s, today).unwrap().to_string();
+        assert_eq!(parse("2026-03-15"), "2026-03-15");
+        assert_eq!(parse("today"), "2026-02-27");
+        assert_eq!(parse("tomorrow"), "2026-02-28");
+        assert_eq!(parse("3d"), "2026-03-02");
+        assert_eq!(parse("1w"), "2026-03-06");
+        // 2026-02-27 is a Friday; a weekday always means the next one.
+        assert_eq!(parse("Monday"), "2026-03-02");
+        assert_eq!(parse("fri"), "2026-03-06");
+        assert!(parse_date("someday", today).is_err());
+    }
+
     #[test]
     fn slugify_simple() {
         assert_eq!(slugify("fix the login bug"), "fix-the-login-bug");
@@ -91,6 +196,41 @@ mod tests {
         assert_eq!(slugify("hello   ---   world"), "hello-world");
     }
 
+    #[test]
+    fn parse_duration_units() {
+        assert_eq!(
+            parse_duration("45m").unwrap(),
+            SignedDuration::from_mins(45)
+        );
+        assert_eq!(
+            parse_duration("1h30m").unwrap(),
+            SignedDuration::from_mins(90)
+        );
+        assert_eq!(
+            parse_duration("2d").unwrap(),
+            SignedDuration::from_hours(48)
+        );
+        assert!(parse_duration("soon").is_err());
+    }
+
+    #[test]
+    fn format_duration_hours_and_minutes() {
+        assert_eq!(format_duration(SignedDuration::from_mins(90)), "1h 30m");
+        assert_eq!(format_duration(SignedDuration::from_mins(45)), "45m");
+        assert_eq!(format_duration(SignedDuration::from_secs(59)), "0m");
+    }
+
+    #[test]
+    fn parse_since_date_and_duration() {
+        assert_eq!(
+            parse_since("2026-02-01").unwrap(),
+            "2026-02-01T00:00:00".parse::<DateTime>().unwrap()
+        );
+        let week_ago = parse_since("7d").unwrap();
+        assert!(week_ago < now());
+        assert!(parse_since("whenever").is_err());
+    }
+
     #[test]
     fn generate_id_format() {
         let id = generate_id(|_| false).unwrap();
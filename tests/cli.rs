@@ -328,3 +328,1153 @@ fn malformed_file_does_not_affect_count() {
     let output = t.run_ok(&["count"]);
     assert_eq!(output, "2", "count should only include valid todos");
 }
+
+#[test]
+fn doctor_clean_store_succeeds() {
+    let t = TdoTest::new();
+    t.run_ok(&["add", "all good"]);
+    let output = t.run(&["doctor"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("no problems found"), "{stderr}");
+}
+
+#[test]
+fn doctor_reports_problems_with_file_and_line() {
+    let t = TdoTest::new();
+    t.write_raw("ab12-broken.md", "no frontmatter");
+    t.write_raw(
+        "cd34-status.md",
+        "---\ntitle: x\ncreated: 2026-02-20T14:30:52\nstatus: closed\n---\n",
+    );
+    t.write_raw("notes.md", "just some notes");
+
+    let output = t.run(&["doctor"]);
+    assert!(!output.status.success(), "problems should fail the check");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("ab12-broken.md:1: missing opening --- delimiter"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("cd34-status.md:4: invalid status 'closed'"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("notes.md: not a todo file"), "{stdout}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("3 problems found"), "{stderr}");
+}
+
+#[test]
+fn doctor_fix_repairs_safe_problems() {
+    let t = TdoTest::new();
+    t.write_raw(
+        "ab12-crlf.md",
+        "---\r\ntitle: crlf\r\ncreated: 2026-02-20T14:30:52\r\nstatus: open\r\n---\r\n",
+    );
+    t.write_raw(
+        "cd34-case.md",
+        "---\ntitle: case\ncreated: 2026-02-20T14:30:52\nstatus: Done\n---\n",
+    );
+    t.write_raw(
+        "ef56-done-at.md",
+        "---\ntitle: done at\ncreated: 2026-02-20T14:30:52\nstatus: open\ndone_at: 2026-02-21T10:00:00\n---\n",
+    );
+    t.write_raw(
+        "no-id.md",
+        "---\ntitle: lost todo\ncreated: 2026-02-20T14:30:52\nstatus: open\n---\n",
+    );
+
+    let stdout = t.run_ok(&["doctor", "--fix"]);
+    assert!(stdout.contains("fixed: ab12-crlf.md"), "{stdout}");
+    assert!(stdout.contains("fixed: cd34-case.md"), "{stdout}");
+    assert!(stdout.contains("fixed: ef56-done-at.md"), "{stdout}");
+    assert!(stdout.contains("fixed: no-id.md: renamed to"), "{stdout}");
+
+    t.run_ok(&["doctor"]);
+    let list = t.run_ok(&["list", "--all"]);
+    for title in ["crlf", "case", "done at", "lost todo"] {
+        assert!(list.contains(title), "{title} should load: {list}");
+    }
+    let content = std::fs::read_to_string(t.dir.path().join("ef56-done-at.md")).unwrap();
+    assert!(!content.contains("done_at"), "{content}");
+}
+
+#[test]
+fn fsck_alias_runs_doctor() {
+    let t = TdoTest::new();
+    let output = t.run(&["fsck"]);
+    assert!(output.status.success());
+}
+
+fn write_duplicates(t: &TdoTest) {
+    t.write_raw(
+        "a3f9-foo.md",
+        "---\ntitle: foo\ncreated: 2026-02-20T14:30:52\nstatus: open\n---\n",
+    );
+    t.write_raw(
+        "a3f9-bar.md",
+        "---\ntitle: bar\ncreated: 2026-02-21T14:30:52\nstatus: open\n---\n",
+    );
+}
+
+#[test]
+fn duplicate_ids_warn_on_load() {
+    let t = TdoTest::new();
+    write_duplicates(&t);
+
+    let output = t.run(&["list"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("warning: duplicate id in a3f9-bar.md, a3f9-foo.md"),
+        "{stderr}"
+    );
+
+    let err = t.run_err(&["done", "a3f9"]);
+    assert!(err.contains("used by multiple files"), "{err}");
+    assert!(err.contains("tdo reid"), "{err}");
+}
+
+#[test]
+fn reid_renames_file_with_fresh_id() {
+    let t = TdoTest::new();
+    write_duplicates(&t);
+
+    let new_id = t.run_ok(&["reid", "a3f9-bar.md"]);
+    assert_eq!(new_id.len(), 4);
+    assert_ne!(new_id, "a3f9");
+    let mut files = t.files();
+    files.sort();
+    assert!(files.contains(&format!("{new_id}-bar.md")), "{files:?}");
+    assert!(files.contains(&"a3f9-foo.md".to_string()), "{files:?}");
+
+    // Both todos are addressable again.
+    t.run_ok(&["done", "a3f9"]);
+    t.run_ok(&["done", &new_id]);
+}
+
+#[test]
+fn doctor_fix_resolves_duplicate_ids() {
+    let t = TdoTest::new();
+    write_duplicates(&t);
+
+    let stdout = t.run_ok(&["doctor", "--fix"]);
+    assert!(
+        stdout.contains("fixed: a3f9-foo.md: renamed to"),
+        "{stdout}"
+    );
+    assert!(t.files().contains(&"a3f9-bar.md".to_string()));
+    let output = t.run(&["list"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("duplicate"), "{stderr}");
+}
+
+#[test]
+fn auto_commit_commits_each_change() {
+    let t = TdoTest::new();
+    t.git_init();
+    t.write_raw("config.yml", "auto_commit: true\n");
+
+    let id = t.run_ok(&["add", "fix the login bug"]);
+    t.run_ok(&["done", &id]);
+
+    let log = t.git(&["log", "--format=%s"]);
+    assert!(
+        log.contains(&format!("tdo: add {id} fix the login bug")),
+        "{log}"
+    );
+    assert!(
+        log.contains(&format!("tdo: done {id} fix the login bug")),
+        "{log}"
+    );
+    let status = t.git(&["status", "--porcelain"]);
+    assert_eq!(status, "?? .lock", "only the lock file stays untracked");
+}
+
+#[test]
+fn no_commits_without_auto_commit() {
+    let t = TdoTest::new();
+    t.git_init();
+    t.run_ok(&["add", "not committed"]);
+    let output = std::process::Command::new("git")
+        .args(["log"])
+        .current_dir(t.dir.path())
+        .output()
+        .unwrap();
+    assert!(
+        !output.status.success(),
+        "repository should have no commits"
+    );
+}
+
+#[test]
+fn sync_pulls_changes_from_upstream() {
+    let remote = tempfile::TempDir::new().unwrap();
+    helpers::git_in(remote.path(), &["init", "--quiet", "--bare"]);
+
+    let other = TdoTest::new();
+    helpers::git_in(
+        other.dir.path(),
+        &["clone", "--quiet", remote.path().to_str().unwrap(), "."],
+    );
+    other.git(&["config", "user.name", "other"]);
+    other.git(&["config", "user.email", "other@example.com"]);
+    other.write_raw("config.yml", "auto_commit: true\n");
+    let id = other.run_ok(&["add", "from the other clone"]);
+    other.git(&["push", "--quiet", "origin", "HEAD"]);
+
+    let t = TdoTest::new();
+    helpers::git_in(
+        t.dir.path(),
+        &["clone", "--quiet", remote.path().to_str().unwrap(), "."],
+    );
+    t.git(&["config", "user.name", "tdo test"]);
+    t.git(&["config", "user.email", "tdo@example.com"]);
+    // config.yml came along with the clone, so this is committed too.
+    t.run_ok(&["add", "local todo"]);
+    other.run_ok(&["done", &id]);
+    other.git(&["push", "--quiet", "origin", "HEAD"]);
+
+    let output = t.run(&["sync"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let list = t.run_ok(&["list", "--all"]);
+    assert!(list.contains("from the other clone"), "{list}");
+    assert!(list.contains("[done]"), "{list}");
+    assert!(list.contains("local todo"), "{list}");
+}
+
+#[test]
+fn merge_driver_merges_frontmatter_edits() {
+    let t = TdoTest::new();
+    t.git_init();
+    let bin = assert_cmd::cargo::cargo_bin!("tdo");
+    t.write_raw(".gitattributes", "*.md merge=tdo\n");
+    t.git(&[
+        "config",
+        "merge.tdo.driver",
+        &format!("{} merge-driver %O %A %B", bin.display()),
+    ]);
+    let id = t.run_ok(&["add", "shared todo"]);
+    t.git(&["add", "--all", "--", ".", ":(exclude).lock"]);
+    t.git(&["commit", "--quiet", "-m", "base"]);
+    let base_branch = t.git(&["rev-parse", "--abbrev-ref", "HEAD"]);
+
+    t.git(&["checkout", "--quiet", "-b", "other"]);
+    t.run_ok(&["assign", &id, "alice"]);
+    t.git(&["commit", "--quiet", "-am", "assign"]);
+
+    t.git(&["checkout", "--quiet", &base_branch]);
+    t.run_ok(&["done", &id]);
+    t.git(&["commit", "--quiet", "-am", "done"]);
+
+    t.git(&["merge", "--quiet", "--no-edit", "other"]);
+    let shown = t.run_ok(&["show", &id]);
+    assert!(shown.contains("status:   done"), "{shown}");
+    assert!(shown.contains("assigned: alice"), "{shown}");
+}
+
+#[test]
+fn merge_driver_reports_body_conflict() {
+    let t = TdoTest::new();
+    let fm = "---\ntitle: x\ncreated: 2026-02-20T14:30:52\nstatus: open\n---\n";
+    t.write_raw("base.md", &format!("{fm}\nbase\n"));
+    t.write_raw("ours.md", &format!("{fm}\nours\n"));
+    t.write_raw("theirs.md", &format!("{fm}\ntheirs\n"));
+    let p = |f: &str| t.dir.path().join(f).to_string_lossy().to_string();
+
+    let output = t.run(&[
+        "merge-driver",
+        &p("base.md"),
+        &p("ours.md"),
+        &p("theirs.md"),
+    ]);
+    assert!(
+        !output.status.success(),
+        "body conflict should fail the merge"
+    );
+    let merged = std::fs::read_to_string(t.dir.path().join("ours.md")).unwrap();
+    assert!(merged.starts_with("---\ntitle: x\n"), "{merged}");
+    assert!(
+        merged.contains("<<<<<<< ours\nours\n=======\ntheirs\n>>>>>>> theirs"),
+        "{merged}"
+    );
+}
+
+#[test]
+fn show_history_lists_events_in_order() {
+    let t = TdoTest::new();
+    let id = t.run_ok(&["add", "tracked"]);
+    t.run_ok(&["assign", &id, "alice"]);
+    t.run_ok(&["edit", &id, "--body", "notes"]);
+    t.run_ok(&["done", &id]);
+    t.run_ok(&["reopen", &id]);
+
+    let output = t.run_ok(&["show", "--history", &id]);
+    let history = output.split("history:").nth(1).expect("history section");
+    let events: Vec<&str> = history
+        .lines()
+        .filter_map(|l| l.split_whitespace().nth(2))
+        .collect();
+    assert_eq!(
+        events,
+        vec!["created", "assigned", "edited", "done", "reopened"],
+        "{output}"
+    );
+    assert!(history.contains("assigned to alice"), "{output}");
+
+    let plain = t.run_ok(&["show", &id]);
+    assert!(!plain.contains("history:"), "{plain}");
+}
+
+#[test]
+fn log_shows_activity_across_todos() {
+    let t = TdoTest::new();
+    let a = t.run_ok(&["add", "first todo"]);
+    let b = t.run_ok(&["add", "second todo"]);
+    t.run_ok(&["done", &a]);
+
+    let log = t.run_ok(&["log"]);
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines.len(), 3, "{log}");
+    assert!(
+        lines[0].contains("created") && lines[0].contains(&a),
+        "{log}"
+    );
+    assert!(
+        lines[1].contains("created") && lines[1].contains(&b),
+        "{log}"
+    );
+    assert!(
+        lines[2].contains("done") && lines[2].contains("first todo"),
+        "{log}"
+    );
+
+    let limited = t.run_ok(&["log", "--limit", "1"]);
+    assert_eq!(limited.lines().count(), 1);
+    assert!(limited.contains("done"), "{limited}");
+}
+
+#[test]
+fn history_is_synthesized_for_old_files() {
+    let t = TdoTest::new();
+    t.write_raw(
+        "ab12-old.md",
+        "---\ntitle: old\ncreated: 2026-02-20T14:30:52\nstatus: open\n---\n",
+    );
+    let output = t.run_ok(&["show", "--history", "ab12"]);
+    assert!(output.contains("2026-02-20 14:30  created"), "{output}");
+}
+
+#[test]
+fn start_and_stop_timer() {
+    let t = TdoTest::new();
+    let a = t.run_ok(&["add", "first"]);
+    let b = t.run_ok(&["add", "second"]);
+
+    t.run_ok(&["start", &a]);
+    let err = t.run_err(&["start", &a]);
+    assert!(err.contains("already running"), "{err}");
+    assert!(t.run_ok(&["show", &a]).contains("(running since"));
+
+    // Starting another todo stops the first timer.
+    let output = t.run(&["start", &b]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains(&format!("stopped: {a}")), "{stderr}");
+    assert!(!t.run_ok(&["show", &a]).contains("running"));
+
+    let output = t.run(&["stop"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains(&format!("stopped: {b}")), "{stderr}");
+
+    let err = t.run_err(&["stop"]);
+    assert!(err.contains("no timer is running"), "{err}");
+}
+
+#[test]
+fn log_time_adds_to_totals_and_report() {
+    let t = TdoTest::new();
+    let a = t.run_ok(&["add", "bigger"]);
+    let b = t.run_ok(&["add", "smaller"]);
+    t.run_ok(&["log-time", &a, "1h30m"]);
+    t.run_ok(&["log-time", &b, "45m"]);
+    t.run_ok(&["log-time", &a, "15m"]);
+
+    let shown = t.run_ok(&["show", &a]);
+    assert!(shown.contains("time:     1h 45m"), "{shown}");
+
+    let report = t.run_ok(&["report", "time", "--since", "1d"]);
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines.len(), 3, "{report}");
+    assert!(
+        lines[0].starts_with(&a) && lines[0].contains("1h 45m"),
+        "{report}"
+    );
+    assert!(
+        lines[1].starts_with(&b) && lines[1].contains("45m"),
+        "{report}"
+    );
+    assert!(
+        lines[2].starts_with("total") && lines[2].contains("2h 30m"),
+        "{report}"
+    );
+
+    t.run_err(&["log-time", &a, "soon"]);
+}
+
+#[test]
+fn done_stops_running_timer() {
+    let t = TdoTest::new();
+    let id = t.run_ok(&["add", "timed"]);
+    t.run_ok(&["start", &id]);
+    t.run_ok(&["done", &id]);
+    t.run_err(&["stop"]);
+    let history = t.run_ok(&["show", "--history", &id]);
+    assert!(history.contains("stopped"), "{history}");
+}
+
+#[test]
+fn estimates_and_report_summary() {
+    let t = TdoTest::new();
+    let a = t.run_ok(&["add", "--estimate", "2h", "estimated"]);
+    let b = t.run_ok(&["add", "other"]);
+    t.run_ok(&["estimate", &b, "1h30m"]);
+    t.run_ok(&["assign", &b, "alice"]);
+    t.run_ok(&["done", &a]);
+
+    let shown = t.run_ok(&["show", &b]);
+    assert!(shown.contains("estimate: 1h 30m"), "{shown}");
+
+    let report = t.run_ok(&["report"]);
+    assert!(report.contains("open:       1 (1 assigned)"), "{report}");
+    assert!(report.contains("done:       1"), "{report}");
+    assert!(report.contains("estimated:  1h 30m open"), "{report}");
+    assert!(report.contains("alice"), "{report}");
+    assert!(report.contains("      2     1"), "{report}");
+
+    let json = t.run_ok(&["report", "--json", "--weeks", "2"]);
+    assert!(json.contains("\"open\": 1"), "{json}");
+    assert!(json.contains("\"open_estimate_secs\": 5400"), "{json}");
+    assert_eq!(json.matches("\"week\":").count(), 2, "{json}");
+
+    t.run_ok(&["estimate", &b, "--clear"]);
+    let shown = t.run_ok(&["show", &b]);
+    assert!(!shown.contains("estimate:"), "{shown}");
+    t.run_err(&["estimate", &b]);
+    t.run_err(&["estimate", &b, "-1h"]);
+}
+
+#[test]
+fn completing_recurring_todo_creates_next_occurrence() {
+    let t = TdoTest::new();
+    let id = t.run_ok(&[
+        "add",
+        "--due",
+        "2099-01-05",
+        "--recur",
+        "weekly",
+        "--estimate",
+        "30m",
+        "review deps",
+    ]);
+    let listed = t.run_ok(&["list"]);
+    assert!(listed.contains("review deps (due 2099-01-05)"), "{listed}");
+
+    let output = t.run(&["done", &id]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("due 2099-01-12"), "{stderr}");
+
+    let listed = t.run_ok(&["list"]);
+    let lines: Vec<&str> = listed.lines().collect();
+    assert_eq!(lines.len(), 1, "{listed}");
+    let next = lines[0].split_whitespace().next().unwrap();
+    assert_ne!(next, id);
+    let shown = t.run_ok(&["show", next]);
+    assert!(shown.contains("due:      2099-01-12"), "{shown}");
+    assert!(shown.contains("recur:    weekly"), "{shown}");
+    assert!(shown.contains(&format!("previous: {id}")), "{shown}");
+    assert!(shown.contains("estimate: 30m"), "{shown}");
+
+    // Completing the same occurrence again doesn't spawn a second one.
+    t.run_ok(&["reopen", &id]);
+    t.run_ok(&["done", &id]);
+    assert_eq!(t.run_ok(&["count"]), "1");
+}
+
+#[test]
+fn due_and_recur_commands() {
+    let t = TdoTest::new();
+    let id = t.run_ok(&["add", "rotate credentials"]);
+    t.run_ok(&["due", &id, "2099-03-31"]);
+    t.run_ok(&["recur", &id, "FREQ=MONTHLY;BYMONTHDAY=31"]);
+    t.run_err(&["recur", &id, "fortnightly"]);
+    t.run_ok(&["done", &id]);
+
+    let listed = t.run_ok(&["list"]);
+    assert!(listed.contains("(due 2099-04-30)"), "{listed}");
+    let next = listed.split_whitespace().next().unwrap();
+    t.run_ok(&["recur", next, "--clear"]);
+    t.run_ok(&["due", next, "--clear"]);
+    t.run_ok(&["done", next]);
+    assert_eq!(t.run_ok(&["count"]), "0");
+}
+
+#[test]
+fn snoozed_todos_are_hidden_until_their_date() {
+    let t = TdoTest::new();
+    let a = t.run_ok(&["add", "actionable"]);
+    let b = t.run_ok(&["add", "later"]);
+    t.run_ok(&["snooze", &b, "3d"]);
+
+    let listed = t.run_ok(&["list"]);
+    assert!(listed.contains(&a) && !listed.contains(&b), "{listed}");
+    assert_eq!(t.run_ok(&["count"]), "1");
+    assert_eq!(t.run_ok(&["count", "--include-snoozed"]), "2");
+    let listed = t.run_ok(&["list", "--include-snoozed"]);
+    assert!(listed.contains("later (snoozed until "), "{listed}");
+    assert!(t.run_ok(&["show", &b]).contains("snoozed:  until "));
+
+    t.run_err(&["snooze", &b, "today"]);
+    t.run_err(&["snooze", &b, "whenever"]);
+
+    t.run_ok(&["snooze", &b, "--clear"]);
+    assert_eq!(t.run_ok(&["count"]), "2");
+    let history = t.run_ok(&["show", "--history", &b]);
+    assert!(history.contains("snoozed until "), "{history}");
+    assert!(history.contains("edited: snooze cleared"), "{history}");
+}
+
+#[test]
+fn multiple_assignees_and_current_user() {
+    let t = TdoTest::new();
+    let a = t.run_ok(&["add", "mine"]);
+    let b = t.run_ok(&["add", "theirs"]);
+    let c = t.run_ok(&["add", "nobody's"]);
+
+    t.run_as("alice", &["assign", &a]);
+    t.run_ok(&["assign", &b, "bob", "carol"]);
+    assert!(t.run_ok(&["show", &a]).contains("assigned: alice"));
+    assert!(t.run_ok(&["show", &b]).contains("assigned: bob, carol"));
+    let file = t.files().into_iter().find(|f| f.starts_with(&b)).unwrap();
+    let raw = std::fs::read_to_string(t.dir.path().join(file)).unwrap();
+    assert!(raw.contains("assigned:\n- bob\n- carol\n"), "{raw}");
+
+    assert_eq!(t.run_as("alice", &["list", "--mine"]).lines().count(), 1);
+    let bobs = t.run_ok(&["list", "--assignee", "bob"]);
+    assert!(bobs.starts_with(&b) && bobs.lines().count() == 1, "{bobs}");
+    let unassigned = t.run_ok(&["list", "--unassigned"]);
+    assert!(unassigned.starts_with(&c) && unassigned.lines().count() == 1);
+
+    t.run_ok(&["unassign", &b, "bob"]);
+    assert!(t.run_ok(&["show", &b]).contains("assigned: carol"));
+    t.run_err(&["unassign", &b, "bob"]);
+
+    // The store config names the user when $TDO_USER isn't set.
+    t.write_raw("config.yml", "user: dave\n");
+    t.run_ok(&["assign", &c]);
+    assert!(t.run_ok(&["show", &c]).contains("assigned: dave"));
+    assert_eq!(t.run_ok(&["list", "--mine"]).lines().count(), 1);
+}
+
+#[test]
+fn claim_takes_oldest_unassigned_and_release_gives_it_back() {
+    let t = TdoTest::new();
+    let a = t.run_ok(&["add", "--tag", "backend", "first"]);
+    let b = t.run_ok(&["add", "--body", "details", "second"]);
+    let c = t.run_ok(&["add", "third"]);
+    t.run_ok(&["assign", &a, "someone"]);
+    t.run_ok(&["tag", &c, "#backend", "urgent"]);
+    assert!(
+        t.run_ok(&["show", &c])
+            .contains("tags:     backend, urgent")
+    );
+
+    let claimed = t.run_as("agent-1", &["claim"]);
+    assert_eq!(claimed, format!("{b}  second\n\ndetails"));
+    assert!(t.run_ok(&["show", &b]).contains("assigned: agent-1"));
+
+    let claimed = t.run_ok(&["claim", "--tag", "backend", "--as", "agent-2"]);
+    assert!(claimed.starts_with(&c), "{claimed}");
+
+    let output = t.run(&["claim", "--as", "agent-3"]);
+    assert_eq!(output.status.code(), Some(3));
+    t.run_err(&["claim"]);
+
+    t.run_err(&["release", &b, "--as", "agent-2"]);
+    t.run_as("agent-1", &["release", &b]);
+    assert!(t.run_ok(&["list", "--unassigned"]).starts_with(&b));
+    t.run_ok(&["untag", &c, "urgent"]);
+    let shown = t.run_ok(&["show", &c]);
+    assert!(shown.contains("tags:     backend") && !shown.contains("urgent"));
+}
+
+#[test]
+fn concurrent_claims_get_distinct_todos() {
+    let t = TdoTest::new();
+    for i in 0..4 {
+        t.run_ok(&["add", &format!("task {i}")]);
+    }
+    let bin = assert_cmd::cargo::cargo_bin!("tdo");
+    let children: Vec<_> = (0..4)
+        .map(|i| {
+            std::process::Command::new(bin)
+                .arg("--dir")
+                .arg(t.dir.path())
+                .args(["claim", "--as", &format!("agent-{i}")])
+                .stdout(std::process::Stdio::piped())
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    let mut ids: Vec<String> = children
+        .into_iter()
+        .map(|child| {
+            let output = child.wait_with_output().unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()[..4].to_string()
+        })
+        .collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 4);
+    assert_eq!(t.run_ok(&["list", "--unassigned"]), "");
+}
+
+#[test]
+fn stale_policy_unassigns_or_tags_old_assignments() {
+    let t = TdoTest::new();
+    let stale_file = |id: &str, created: &str| {
+        format!(
+            "---\ntitle: old {id}\ncreated: {created}T09:00:00\nstatus: open\n\
+             assigned: alice\nassigned_at: 2026-01-02T09:00:00\n---\n"
+        )
+    };
+    t.write_raw("ab12-old-ab12.md", &stale_file("ab12", "2026-01-01"));
+    let fresh = t.run_ok(&["add", "fresh"]);
+    t.run_ok(&["assign", &fresh, "bob"]);
+
+    // The default policy only reports; nothing changes.
+    let stale = t.run_ok(&["stale"]);
+    assert!(
+        stale.starts_with("ab12  old ab12 (assigned: alice) ("),
+        "{stale}"
+    );
+    assert!(!stale.contains(&fresh));
+    assert!(t.run_ok(&["show", "ab12"]).contains("assigned: alice"));
+
+    t.write_raw("config.yml", "stale_after: 2d\nstale_action: tag\n");
+    t.run_ok(&["list"]);
+    let shown = t.run_ok(&["show", "--history", "ab12"]);
+    assert!(shown.contains("assigned: alice"), "{shown}");
+    assert!(shown.contains("tags:     stale"), "{shown}");
+    assert!(shown.contains("stale: tagged stale"), "{shown}");
+
+    t.write_raw("cd34-old-cd34.md", &stale_file("cd34", "2025-12-01"));
+    t.write_raw("config.yml", "stale_action: unassign\n");
+    let claimed = t.run_ok(&["claim", "--as", "carol"]);
+    assert!(claimed.starts_with("cd34"), "{claimed}");
+    let shown = t.run_ok(&["show", "--history", "ab12"]);
+    assert!(!shown.contains("assigned:"), "{shown}");
+    assert!(shown.contains("stale: unassigned alice"), "{shown}");
+    assert_eq!(t.run_ok(&["stale"]), "");
+
+    t.write_raw("config.yml", "stale_after: tomorrow\n");
+    t.run_err(&["stale"]);
+}
+
+#[test]
+fn status_workflow() {
+    let t = TdoTest::new();
+    t.write_raw(
+        "config.yml",
+        "statuses:\n- in-progress\n- {name: review, color: blue}\n- {name: wontfix, open: false}\n",
+    );
+    let id = t.run_ok(&["add", "ship it"]);
+    let other = t.run_ok(&["add", "other"]);
+
+    t.run_ok(&["status", &id, "review"]);
+    assert_eq!(
+        t.run_ok(&["list"]).lines().next().unwrap(),
+        format!("{id}  [review] ship it")
+    );
+    let shown = t.run_ok(&["show", "--history", &id]);
+    assert!(shown.contains("status:   review"), "{shown}");
+    assert!(shown.contains("status: review"), "{shown}");
+
+    // Only todos in `open` are up for claiming.
+    let claimed = t.run_ok(&["claim", "--as", "agent"]);
+    assert!(claimed.starts_with(&other), "{claimed}");
+
+    t.run_ok(&["status", &id, "wontfix"]);
+    assert!(!t.run_ok(&["list"]).contains(&id));
+    assert!(
+        t.run_ok(&["list", "--all"])
+            .contains(&format!("{id}  [wontfix] ship it"))
+    );
+
+    let err = t.run_err(&["status", &id, "bogus"]);
+    assert!(
+        err.contains(
+            "unknown status 'bogus' (expected open, done, cancelled, in-progress, review, wontfix)"
+        ),
+        "{err}"
+    );
+
+    t.run_ok(&["status", &id, "done"]);
+    assert!(t.run_ok(&["show", &id]).contains("done_at:"));
+    t.run_ok(&["status", &id, "open"]);
+    assert!(t.run_ok(&["show", "--history", &id]).contains("reopened"));
+
+    // Files in states that aren't configured are skipped with a warning.
+    t.run_ok(&["status", &id, "in-progress"]);
+    t.write_raw("config.yml", "");
+    let output = t.run(&["list"]);
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("unknown status 'in-progress'"),
+        "{output:?}"
+    );
+    let doctor = t.run(&["doctor"]);
+    assert!(
+        String::from_utf8_lossy(&doctor.stdout)
+            .contains("invalid status 'in-progress' (expected open, done, cancelled)"),
+        "{doctor:?}"
+    );
+}
+
+#[test]
+fn cancel_keeps_the_record_apart_from_done() {
+    let t = TdoTest::new();
+    let id = t.run_ok(&["add", "rewrite in rust"]);
+    let done = t.run_ok(&["add", "ship"]);
+    t.run_ok(&["done", &done]);
+
+    t.run_ok(&["cancel", &id, "--reason", "already is"]);
+    assert!(!t.run_ok(&["list"]).contains(&id));
+    let all = t.run_ok(&["list", "--all"]);
+    assert!(
+        all.contains(&format!("{id}  [cancelled] rewrite in rust")),
+        "{all}"
+    );
+    assert!(all.contains(&format!("{done}  [done] ship")), "{all}");
+
+    let shown = t.run_ok(&["show", "--history", &id]);
+    assert!(shown.contains("status:   cancelled"), "{shown}");
+    assert!(shown.contains("closed:   "), "{shown}");
+    assert!(!shown.contains("done_at"), "{shown}");
+    assert!(shown.contains("cancelled: already is"), "{shown}");
+
+    let report = t.run_ok(&["report"]);
+    assert!(report.contains("done:       1"), "{report}");
+    assert!(report.contains("cancelled:  1"), "{report}");
+
+    t.run_ok(&["reopen", &id]);
+    let shown = t.run_ok(&["show", &id]);
+    assert!(shown.contains("status:   open"), "{shown}");
+    assert!(!shown.contains("cancelled"), "{shown}");
+
+    // Cancelled todos are garbage-collected like done ones.
+    t.write_raw(
+        "ab12-old.md",
+        "---\ntitle: old\ncreated: 2026-01-01T09:00:00\nstatus: cancelled\n\
+         cancelled_at: 2026-01-02T09:00:00\n---\n",
+    );
+    let output = t.run(&["list"]);
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("removed cancelled todo ab12"),
+        "{output:?}"
+    );
+    assert!(!t.files().iter().any(|f| f.starts_with("ab12")));
+}
+
+#[test]
+fn import_todotxt_with_dry_run_and_dedupe() {
+    let t = TdoTest::new();
+    t.run_ok(&["add", "call mom"]);
+    let export = tempfile::TempDir::new().unwrap();
+    let path = export.path().join("todo.txt");
+    std::fs::write(
+        &path,
+        "(A) 2026-01-20 Call  Mom\n2026-01-21 plan trip +travel due:2026-03-01\n\
+         x 2026-02-03 2026-01-02 file taxes\nplan trip +travel\n",
+    )
+    .unwrap();
+    let path = path.to_str().unwrap();
+
+    let preview = t.run_ok(&["import", "--from", "todotxt", path, "--dry-run"]);
+    assert_eq!(preview, "plan trip +travel  [open]");
+    assert_eq!(t.run_ok(&["count", "--all"]), "1");
+
+    let imported = t.run_ok(&["import", "--from", "todotxt", path, "--all"]);
+    assert_eq!(imported.lines().count(), 2, "{imported}");
+    let trip = imported.lines().next().unwrap().split("  ").next().unwrap();
+    let shown = t.run_ok(&["show", "--history", trip]);
+    assert!(shown.contains("tags:     travel"), "{shown}");
+    assert!(shown.contains("due:      2026-03-01"), "{shown}");
+    assert!(shown.contains("imported from todo.txt"), "{shown}");
+
+    // Closed items outlive the next list's GC.
+    let all = t.run_ok(&["list", "--all"]);
+    assert!(all.contains("[done] file taxes"), "{all}");
+    t.run_ok(&["list"]);
+    assert_eq!(t.run_ok(&["count", "--all"]), "3");
+
+    // Importing again finds every title already there.
+    assert_eq!(t.run_ok(&["import", "--from", "todotxt", path]), "");
+
+    // One bad item stops the import before anything is written.
+    std::fs::write(path, "first\nsecond +p1,urgent\n").unwrap();
+    let err = t.run_err(&["import", "--from", "todotxt", path]);
+    assert!(err.contains("can't import 'second +p1,urgent'"), "{err}");
+    assert_eq!(t.run_ok(&["count", "--all"]), "3");
+    assert!(
+        t.run_err(&["import", "--from", "csv", path])
+            .contains("csv")
+    );
+}
+
+#[test]
+fn export_formats() {
+    let t = TdoTest::new();
+    let id = t.run_ok(&["add", "ship it", "--due", "2026-03-01", "--tag", "release"]);
+    let done = t.run_ok(&["add", "write docs"]);
+    t.run_ok(&["done", &done]);
+
+    let md = t.run_ok(&["export", "--to", "markdown"]);
+    assert_eq!(md, "- [ ] ship it · due 2026-03-01");
+    let all = t.run_ok(&["export", "--to", "markdown", "--all"]);
+    assert!(all.contains("- [x] write docs"), "{all}");
+
+    let txt = t.run_ok(&["export", "--to", "todotxt"]);
+    assert!(txt.ends_with(" ship it +release due:2026-03-01"), "{txt}");
+
+    let csv = t.run_ok(&["export", "--to", "csv"]);
+    assert!(csv.starts_with("id,title,status,"), "{csv}");
+    assert!(csv.contains(&format!("{id},ship it,open,")), "{csv}");
+
+    let out = tempfile::TempDir::new().unwrap();
+    let path = out.path().join("todos.ics");
+    t.run_ok(&[
+        "export",
+        "--to",
+        "ics",
+        "--all",
+        "--output",
+        path.to_str().unwrap(),
+    ]);
+    let ics = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(ics.matches("BEGIN:VTODO").count(), 2, "{ics}");
+    assert!(ics.contains("DUE;VALUE=DATE:20260301\r\n"), "{ics}");
+    assert!(ics.contains("STATUS:COMPLETED\r\nCOMPLETED:"), "{ics}");
+}
+
+#[test]
+fn body_and_todos_from_stdin() {
+    let t = TdoTest::new();
+    let id = t.run_stdin(&["add", "notes", "--body", "-"], "line one\nline two\n");
+    assert!(t.run_ok(&["show", &id]).ends_with("line one\nline two"));
+
+    let out = tempfile::TempDir::new().unwrap();
+    let path = out.path().join("body.md");
+    std::fs::write(&path, "from a file\n").unwrap();
+    t.run_ok(&["edit", &id, "--body-file", path.to_str().unwrap()]);
+    assert!(t.run_ok(&["show", &id]).ends_with("from a file"));
+    t.run_stdin(&["edit", &id, "--body", "-"], "piped edit\n");
+    assert!(t.run_ok(&["show", &id]).ends_with("piped edit"));
+
+    let created = t.run_stdin(
+        &["add", "--from-stdin", "--tag", "triage"],
+        "- [ ] first\n\n* second\nthird\n",
+    );
+    let lines: Vec<&str> = created.lines().collect();
+    assert_eq!(lines.len(), 3, "{created}");
+    assert!(lines[0].ends_with("  first"), "{created}");
+    assert!(lines[1].ends_with("  second"), "{created}");
+    let first = lines[0].split("  ").next().unwrap();
+    assert!(t.run_ok(&["show", first]).contains("triage"));
+
+    let created = t.run_stdin(
+        &["add", "--from-stdin"],
+        "---\ntitle: write spec\ndue: 2026-03-01\n---\n\nIntro\n\n---\n\nMore\n\
+         ---\ntitle: review spec\ntags: [docs]\n---\n",
+    );
+    let lines: Vec<&str> = created.lines().collect();
+    assert_eq!(lines.len(), 2, "{created}");
+    let spec = lines[0].split("  ").next().unwrap();
+    let shown = t.run_ok(&["show", spec]);
+    assert!(shown.contains("due:      2026-03-01"), "{shown}");
+    assert!(shown.ends_with("Intro\n\n---\n\nMore"), "{shown}");
+
+    // A bad line in the batch creates nothing.
+    let count = t.run_ok(&["count"]);
+    let output = t.run_with_stdin(
+        &["add", "--from-stdin"],
+        "---\ntitle: ok\n---\n---\ntitle: bad\ndue: someday\n---\n",
+    );
+    assert!(!output.status.success());
+    assert_eq!(t.run_ok(&["count"]), count);
+    t.run_err(&["add", "title", "--from-stdin"]);
+}
+
+#[test]
+fn notes_are_appended_without_touching_the_body() {
+    let t = TdoTest::new();
+    let id = t.run_ok(&["add", "flaky test", "--body", "Seen on CI."]);
+    t.run_as("alice", &["note", &id, "reproduced", "locally"]);
+    t.run_stdin(
+        &["comment", &id, "-", "--as", "bot"],
+        "stack trace\nline 2\n",
+    );
+
+    let shown = t.run_ok(&["show", &id]);
+    assert!(shown.contains("Seen on CI.\n\nnotes:\n"), "{shown}");
+    assert!(shown.contains("  alice: reproduced locally\n"), "{shown}");
+    // Continuation lines line up under the text: "  YYYY-MM-DD HH:MM  bot: ".
+    let continued = format!("  bot: stack trace\n{}line 2", " ".repeat(25));
+    assert!(shown.ends_with(&continued), "{shown}");
+
+    // Each note is in the history too, by its first line.
+    let history = t.run_ok(&["show", "--history", &id]);
+    assert!(history.contains("noted: reproduced locally\n"), "{history}");
+    assert!(t.run_ok(&["log"]).contains("noted: stack trace"));
+
+    // Without an identity the note is unattributed.
+    t.run_ok(&["note", &id, "anonymous"]);
+    assert!(t.run_ok(&["show", &id]).ends_with(": anonymous"));
+    assert!(t.run_err(&["note", &id, " "]).contains("empty"));
+}
+
+#[test]
+fn templates_fill_in_new_bodies() {
+    let t = TdoTest::new();
+    std::fs::create_dir(t.dir.path().join("templates")).unwrap();
+    t.write_raw(
+        "templates/bug.md",
+        "Reported by {{user}} on {{date}}.\n\n## Repro\n\n## Expected\n\n## Actual\n",
+    );
+    t.write_raw("templates/feature.md", "## Why\n");
+
+    let id = t.run_as("alice", &["add", "--template", "bug", "login", "fails"]);
+    let shown = t.run_ok(&["show", &id]);
+    assert!(shown.contains("Reported by alice on "), "{shown}");
+    assert!(shown.ends_with("## Expected\n\n## Actual"), "{shown}");
+
+    let err = t.run_err(&["add", "--template", "chore", "x"]);
+    assert!(err.contains("available: bug, feature"), "{err}");
+
+    // The default template applies unless a body is given.
+    t.write_raw("config.yml", "default_template: feature\n");
+    let id = t.run_ok(&["add", "dark mode"]);
+    assert!(t.run_ok(&["show", &id]).ends_with("## Why"));
+    let id = t.run_ok(&["add", "plain", "--body", "just this"]);
+    assert!(t.run_ok(&["show", &id]).ends_with("just this"));
+    assert_eq!(t.run_ok(&["count"]), "3");
+}
+
+#[test]
+fn completions_offer_todo_ids() {
+    let t = TdoTest::new();
+    let first = t.run_ok(&["add", "first thing"]);
+    let done = t.run_ok(&["add", "finished"]);
+    t.run_ok(&["done", &done]);
+
+    let script = t.run_ok(&["completions", "bash"]);
+    // Subcommands and flags are in the script itself...
+    assert!(script.contains("tdo,log-time)"), "{script}");
+    assert!(script.contains("--history"), "{script}");
+    // ...and only IDs come from calling back into tdo, like this.
+    assert!(script.contains("COMPLETE=ids"), "{script}");
+
+    let dir = t.dir.path().to_str().unwrap();
+    let complete = |words: &[&str]| {
+        let output = std::process::Command::new(assert_cmd::cargo::cargo_bin!("tdo"))
+            .env("COMPLETE", "ids")
+            .arg("--")
+            .args(words)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    // Open todos come first.
+    assert_eq!(
+        complete(&["--dir", dir, "show", "--history", ""]),
+        format!("{first}\tfirst thing\n{done}\tfinished\n")
+    );
+    assert_eq!(
+        complete(&["view", &format!("--dir={dir}"), &done]),
+        format!("{done}\tfinished\n")
+    );
+    // Not where an ID goes.
+    assert_eq!(complete(&["--dir", dir, ""]), "");
+    assert_eq!(complete(&["--dir", dir, "tag", &first, ""]), "");
+    assert_eq!(complete(&["--dir", dir, "reid", ""]), "");
+}
+
+#[test]
+fn todos_can_be_addressed_by_title() {
+    let t = TdoTest::new();
+    let bug = t.run_ok(&["add", "login bug on safari"]);
+    let slow = t.run_ok(&["add", "login page slow"]);
+    let old = t.run_ok(&["add", "login page slow"]);
+    t.run_ok(&["done", &old]);
+
+    // Prefix, then all words, then characters in order.
+    assert!(t.run_ok(&["show", "login bug"]).starts_with(&bug));
+    assert!(t.run_ok(&["show", "page login"]).starts_with(&slow));
+    assert!(t.run_ok(&["show", "lgn slw"]).starts_with(&slow));
+
+    // The open todo wins over the done one with the same title.
+    t.run_ok(&["tag", "LOGIN PAGE SLOW", "perf"]);
+    assert!(t.run_ok(&["show", &slow]).contains("perf"));
+
+    let err = t.run_err(&["done", "login"]);
+    assert!(
+        err.contains("ambiguous query 'login' matches 2 todos"),
+        "{err}"
+    );
+    assert!(
+        err.contains(&format!("{bug}  login bug on safari")),
+        "{err}"
+    );
+
+    // `@` skips ID prefixes, for titles that look like one.
+    let hex = t.run_ok(&["add", "add bead"]);
+    assert!(t.run_ok(&["show", "@add"]).starts_with(&hex));
+}
+
+#[test]
+fn omitted_id_needs_a_terminal_to_pick() {
+    let t = TdoTest::new();
+    t.run_ok(&["add", "something"]);
+    for cmd in ["done", "show", "edit", "cancel"] {
+        let err = t.run_err(&[cmd]);
+        assert!(err.contains("no todo given"), "{cmd}: {err}");
+    }
+    // An empty ID is rejected rather than taken as left out.
+    let err = t.run_err(&["done", ""]);
+    assert!(err.contains("must not be empty"), "{err}");
+    assert_eq!(t.run_ok(&["count"]), "1");
+}
+
+#[test]
+fn a_lone_value_leaves_the_todo_to_pick() {
+    let t = TdoTest::new();
+    let id = t.run_ok(&["add", "something"]);
+    for args in [
+        &["tag", "backend"][..],
+        &["status", "review"],
+        &["due", "tomorrow"],
+        &["note", "checked"],
+        &["assign"],
+    ] {
+        let err = t.run_err(args);
+        assert!(err.contains("no todo given"), "{args:?}: {err}");
+    }
+    // Without a value the command still says what's missing.
+    let err = t.run_err(&["status"]);
+    assert!(err.contains("<STATE>"), "{err}");
+    // With --clear, a lone positional is the ID.
+    t.run_ok(&["due", &id, "tomorrow"]);
+    t.run_ok(&["due", &id, "--clear"]);
+    assert!(!t.run_ok(&["show", &id]).contains("due:"));
+    let err = t.run_err(&["due", &id, "tomorrow", "--clear"]);
+    assert!(err.contains("--clear takes no value"), "{err}");
+}
+
+#[test]
+fn listing_closed_todos_does_not_collect_them() {
+    use jiff::ToSpan;
+
+    let t = TdoTest::new();
+    let done_at = jiff::Zoned::now().datetime().saturating_sub(20.days());
+    t.write_raw(
+        "ab12-shipped.md",
+        &format!(
+            "---\ntitle: shipped\ncreated: 2026-01-01T09:00:00\nstatus: done\n\
+             done_at: {done_at}\n---\n"
+        ),
+    );
+    let output = t.run(&["list", "--done-since", "30d"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("ab12  [done] shipped"), "{output:?}");
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("gc"));
+    assert_eq!(t.run_ok(&["count", "--done-since", "30d"]), "1");
+    assert!(t.run_ok(&["list", "--all"]).contains("ab12"));
+
+    // A query that leaves it out collects it as before.
+    t.run_ok(&["list"]);
+    assert!(!t.files().iter().any(|f| f.starts_with("ab12")));
+}
+
+#[test]
+fn list_filters_and_sorts() {
+    let t = TdoTest::new();
+    let zebra = t.run_ok(&["add", "zebra crossing"]);
+    let apple = t.run_ok(&["add", "apple pie", "--body", "needs Cinnamon"]);
+    let mango = t.run_ok(&["add", "mango"]);
+    t.run_ok(&["assign", &apple, "alice"]);
+    t.run_ok(&["done", &mango]);
+    let ids = |args: &[&str]| -> Vec<String> {
+        let mut full = vec!["list"];
+        full.extend_from_slice(args);
+        t.run_ok(&full)
+            .lines()
+            .map(|l| l.split_whitespace().next().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(ids(&[]), [zebra.as_str(), apple.as_str()]);
+    assert_eq!(ids(&["--sort", "title"]), [apple.as_str(), zebra.as_str()]);
+    assert_eq!(ids(&["--reverse", "--limit", "1"]), [apple.as_str()]);
+    assert_eq!(ids(&["--assigned"]), [apple.as_str()]);
+    assert_eq!(ids(&["--unassigned"]), [zebra.as_str()]);
+    assert_eq!(ids(&["--grep", "cinnamon"]), [apple.as_str()]);
+    // Asking for done todos doesn't need --all.
+    assert_eq!(ids(&["--status", "done"]), [mango.as_str()]);
+    assert_eq!(ids(&["--done-since", "1d"]), [mango.as_str()]);
+    assert_eq!(
+        ids(&["--all", "--sort", "done_at"]),
+        [mango.as_str(), zebra.as_str(), apple.as_str()]
+    );
+    assert!(ids(&["--created-before", "1d"]).is_empty());
+    assert_eq!(ids(&["--created-after", "1d"]).len(), 2);
+
+    // count takes the same filters.
+    assert_eq!(t.run_ok(&["count", "--all", "--grep", "a"]), "3");
+    assert_eq!(t.run_ok(&["count", "--unassigned"]), "1");
+    let err = t.run_err(&["list", "--status", "blocked"]);
+    assert!(err.contains("unknown status 'blocked'"), "{err}");
+}
+
+#[test]
+fn list_templates() {
+    let t = TdoTest::new();
+    let id = t.run_ok(&["add", "fix the login page", "--tag", "auth"]);
+    t.run_ok(&["assign", &id, "alice"]);
+
+    let line = t.run_ok(&[
+        "list",
+        "--template",
+        "{id}|{status:>6}|{title:.8}|{assigned}|{tags}|{created:ago}",
+    ]);
+    assert_eq!(line, format!("{id}|  open|fix the…|alice|#auth|just now"));
+
+    // The config default applies to plain `tdo` too, and --template wins.
+    t.write_raw("config.yml", "list_template: '{title} [{id}]'\n");
+    assert_eq!(t.run_ok(&["list"]), format!("fix the login page [{id}]"));
+    assert_eq!(t.run_ok(&[]), format!("fix the login page [{id}]"));
+    assert_eq!(t.run_ok(&["list", "--template", "{id}"]), id);
+
+    let err = t.run_err(&["list", "--template", "{owner}"]);
+    assert!(err.contains("unknown field '{owner}'"), "{err}");
+    t.write_raw("config.yml", "list_template: '{title:ago}'\n");
+    let err = t.run_err(&["list"]);
+    assert!(err.contains("invalid config"), "{err}");
+}
@@ -1,4 +1,6 @@
-use std::process::{Command, Output};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
 
 use tempfile::TempDir;
 
@@ -15,13 +17,63 @@ impl TdoTest {
 
     /// Run `tdo --dir <tmpdir> <args...>` and return the output.
     pub fn run(&self, args: &[&str]) -> Output {
+        self.command(args).output().expect("failed to execute tdo")
+    }
+
+    /// Like `run_ok`, with `$TDO_USER` set to `user`.
+    pub fn run_as(&self, user: &str, args: &[&str]) -> String {
+        let output = self
+            .command(args)
+            .env("TDO_USER", user)
+            .output()
+            .expect("failed to execute tdo");
+        assert!(
+            output.status.success(),
+            "tdo {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    /// Like `run`, with `input` piped to stdin.
+    pub fn run_with_stdin(&self, args: &[&str], input: &str) -> Output {
+        let mut child = self
+            .command(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to execute tdo");
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    /// Like `run_ok`, with `input` piped to stdin.
+    pub fn run_stdin(&self, args: &[&str], input: &str) -> String {
+        let output = self.run_with_stdin(args, input);
+        assert!(
+            output.status.success(),
+            "tdo {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
         let bin = assert_cmd::cargo::cargo_bin!("tdo");
-        Command::new(bin)
-            .arg("--dir")
+        let mut cmd = Command::new(bin);
+        cmd.arg("--dir")
             .arg(self.dir.path())
             .args(args)
-            .output()
-            .expect("failed to execute tdo")
+            .env_remove("TDO_USER");
+        cmd
     }
 
     /// Convenience: run and return stdout as a trimmed string.
@@ -52,6 +104,18 @@ impl TdoTest {
         std::fs::write(self.dir.path().join(filename), content).unwrap();
     }
 
+    /// Run `git <args>` in the todo directory, asserting it succeeds.
+    pub fn git(&self, args: &[&str]) -> String {
+        git_in(self.dir.path(), args)
+    }
+
+    /// Turn the todo directory into a git repository with a committer identity.
+    pub fn git_init(&self) {
+        self.git(&["init", "--quiet"]);
+        self.git(&["config", "user.name", "tdo test"]);
+        self.git(&["config", "user.email", "tdo@example.com"]);
+    }
+
     /// List todo files (excluding hidden files like .lock) in the todo directory.
     pub fn files(&self) -> Vec<String> {
         std::fs::read_dir(self.dir.path())
@@ -67,3 +131,19 @@ impl TdoTest {
             .collect()
     }
 }
+
+/// Run `git <args>` in `dir`, asserting it succeeds, and return trimmed stdout.
+pub fn git_in(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("failed to execute git");
+    assert!(
+        output.status.success(),
+        "git {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}